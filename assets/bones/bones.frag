@@ -5,7 +5,13 @@ in vec3 Color;
 
 out vec4 FragColor;
 
+uniform int UseGhost;
+uniform vec4 GhostColor;
+
 void main()
 {       
     FragColor = vec4(Color, 1.0);
+    if (UseGhost == 1) {
+        FragColor = GhostColor;
+    }
 }
\ No newline at end of file
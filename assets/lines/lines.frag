@@ -3,7 +3,9 @@
 
 in vec3 Color;
 
+out vec4 FragColor;
+
 void main()
 {       
-    gl_FragColor = vec4(Color, 1.0);
+    FragColor = vec4(Color, 1.0);
 }
\ No newline at end of file
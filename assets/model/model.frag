@@ -2,12 +2,94 @@
 //author https://github.com/autergame
 
 in vec2 UV;
+in float Heat;
+in vec3 Normal;
+in vec3 Position;
+in vec3 ViewNormal;
+in vec3 JointColor;
 
 out vec4 FragColor;
 
 uniform sampler2D Diffuse;
+uniform int UseGhost;
+uniform vec4 GhostColor;
+uniform int HeatmapMode;
+uniform int ShadingMode;
+uniform vec3 LightDirection;
+uniform vec3 LightColor;
+uniform float Ambient;
+uniform float Specular;
+uniform float Shininess;
+uniform vec3 CameraPosition;
+uniform int RenderMode;
+uniform vec3 SubmeshColor;
+uniform vec2 DepthRange;
+uniform int UseOverlay;
+uniform vec4 OverlayColor;
+
+vec3 HeatmapColor(float Value)
+{
+	vec3 Low = mix(vec3(0.0, 0.0, 1.0), vec3(0.0, 1.0, 1.0), clamp(Value * 4.0, 0.0, 1.0));
+	vec3 Middle = mix(Low, vec3(0.0, 1.0, 0.0), clamp(Value * 4.0 - 1.0, 0.0, 1.0));
+	vec3 High = mix(Middle, vec3(1.0, 1.0, 0.0), clamp(Value * 4.0 - 2.0, 0.0, 1.0));
+	return mix(High, vec3(1.0, 0.0, 0.0), clamp(Value * 4.0 - 3.0, 0.0, 1.0));
+}
+
+vec3 UVChecker(vec2 Coords)
+{
+	vec2 Cell = floor(Coords * 8.0);
+	float Checker = mod(Cell.x + Cell.y, 2.0);
+	vec3 Gradient = vec3(fract(Coords), 0.5);
+	if (any(lessThan(Coords, vec2(0.0))) || any(greaterThan(Coords, vec2(1.0)))) {
+		Gradient = vec3(1.0, 0.0, 1.0);
+	}
+	return Gradient * mix(0.55, 1.0, Checker);
+}
+
+vec3 Matcap(vec3 N)
+{
+	float Facing = max(N.z, 0.0);
+	float Rim = pow(1.0 - Facing, 3.0);
+	float Highlight = pow(max(dot(N, normalize(vec3(-0.4, 0.6, 1.0))), 0.0), 24.0);
+	return vec3(0.75, 0.62, 0.55) * (0.3 + 0.7 * Facing) + Rim * 0.35 + Highlight * 0.6;
+}
 
 void main()
 {
 	FragColor = texture(Diffuse, UV);
+	if (RenderMode == 1) {
+		FragColor = vec4(normalize(Normal) * 0.5 + 0.5, 1.0);
+	} else if (RenderMode == 2) {
+		FragColor = vec4(UVChecker(UV), 1.0);
+	} else if (RenderMode == 3) {
+		FragColor = vec4(Matcap(normalize(gl_FrontFacing ? ViewNormal : -ViewNormal)), 1.0);
+	} else if (RenderMode == 4) {
+		float Depth = (distance(CameraPosition, Position) - DepthRange.x) / max(DepthRange.y - DepthRange.x, 0.0001);
+		FragColor = vec4(vec3(1.0 - clamp(Depth, 0.0, 1.0)), 1.0);
+	} else if (RenderMode == 5) {
+		FragColor = vec4(SubmeshColor, 1.0);
+	} else if (RenderMode == 6) {
+		FragColor = vec4(JointColor, 1.0);
+	}
+
+	bool Lit = RenderMode == 0 || RenderMode == 2 || RenderMode == 5 || RenderMode == 6;
+	if (HeatmapMode > 0) {
+		FragColor = vec4(HeatmapColor(Heat), 1.0);
+	} else if (ShadingMode > 0 && Lit) {
+		vec3 N = normalize(gl_FrontFacing ? Normal : -Normal);
+		vec3 L = normalize(LightDirection);
+		vec3 Light = LightColor * (Ambient + (1.0 - Ambient) * max(dot(N, L), 0.0));
+		vec3 Color = FragColor.rgb * Light;
+		if (ShadingMode == 2) {
+			vec3 H = normalize(L + normalize(CameraPosition - Position));
+			Color += LightColor * Specular * pow(max(dot(N, H), 0.0), Shininess);
+		}
+		FragColor = vec4(Color, FragColor.a);
+	}
+	if (UseGhost == 1) {
+		FragColor = vec4(mix(FragColor.rgb, GhostColor.rgb, 0.6), GhostColor.a);
+	}
+	if (UseOverlay == 1) {
+		FragColor = OverlayColor;
+	}
 }
\ No newline at end of file
@@ -5,28 +5,79 @@ layout (location = 0) in vec3 Positions;
 layout (location = 1) in vec2 UVs;
 layout (location = 2) in uvec4 BoneIndices;
 layout (location = 3) in vec4 BoneWeights;
+layout (location = 4) in vec3 Normals;
 
 out vec2 UV;
+out float Heat;
+out vec3 Normal;
+out vec3 Position;
+out vec3 ViewNormal;
+out vec3 JointColor;
 
 uniform mat4 MVP;
+uniform mat4 ModelView;
 uniform int UseBone;
+uniform int HeatmapMode;
+uniform int HeatmapJoint;
+uniform int RenderMode;
 
 layout (std140) uniform BonesTransformsBlock {
     mat4 BonesTransforms[256];
 };
 
+vec3 IdColor(uint Id)
+{
+	uint Hash = (Id + 1u) * 2654435761u;
+	return vec3((Hash >> 24u) & 255u, (Hash >> 16u) & 255u, (Hash >> 8u) & 255u) / 255.0;
+}
+
 void main()
 {
     UV = UVs;
 
+	Heat = 0.0;
+	if (HeatmapMode == 1) {
+		for (int i = 0; i < 4; i++) {
+			if (BoneIndices[i] == uint(HeatmapJoint)) {
+				Heat += BoneWeights[i];
+			}
+		}
+	} else if (HeatmapMode == 2) {
+		for (int i = 0; i < 4; i++) {
+			if (BoneWeights[i] > 0.0) {
+				Heat += 0.25;
+			}
+		}
+	} else if (HeatmapMode == 3) {
+		float WeightSum = BoneWeights[0] + BoneWeights[1] + BoneWeights[2] + BoneWeights[3];
+		Heat = clamp(abs(WeightSum - 1.0) * 10.0, 0.0, 1.0);
+	}
+
 	if (UseBone == 1) {
 		mat4 BoneTransform = BonesTransforms[BoneIndices[0]] * BoneWeights[0];
 		BoneTransform     += BonesTransforms[BoneIndices[1]] * BoneWeights[1];
 		BoneTransform     += BonesTransforms[BoneIndices[2]] * BoneWeights[2];
 		BoneTransform     += BonesTransforms[BoneIndices[3]] * BoneWeights[3];
 
-		gl_Position = MVP * BoneTransform * vec4(Positions, 1.0);
+		Normal = transpose(inverse(mat3(BoneTransform))) * Normals;
+		Position = vec3(BoneTransform * vec4(Positions, 1.0));
 	} else {
-		gl_Position = MVP * vec4(Positions, 1.0);
+		Normal = Normals;
+		Position = Positions;
 	}
+
+	ViewNormal = mat3(ModelView) * Normal;
+
+	JointColor = vec3(0.0);
+	if (RenderMode == 6) {
+		int Dominant = 0;
+		for (int i = 1; i < 4; i++) {
+			if (BoneWeights[i] > BoneWeights[Dominant]) {
+				Dominant = i;
+			}
+		}
+		JointColor = IdColor(BoneIndices[Dominant]);
+	}
+
+	gl_Position = MVP * vec4(Position, 1.0);
 }
\ No newline at end of file
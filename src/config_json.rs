@@ -59,6 +59,121 @@ pub struct OptionsJson {
 
     #[serde(rename = "SelectedAnimation")]
     pub selected_animation_path: String,
+
+    #[serde(rename = "AnimationLayers", default)]
+    pub animation_layers: Vec<LayerJson>,
+
+    #[serde(rename = "Retarget", default)]
+    pub retarget: Option<RetargetJson>,
+
+    #[serde(rename = "LockRootMotion", default)]
+    pub lock_root_motion: bool,
+
+    #[serde(rename = "AccumulateRootMotion", default)]
+    pub accumulate_root_motion: bool,
+
+    #[serde(rename = "ShowRootPath", default)]
+    pub show_root_path: bool,
+
+    #[serde(rename = "PlaybackMode", default)]
+    pub playback_mode: PlaybackMode,
+
+    #[serde(rename = "UseAnimationRange", default)]
+    pub use_animation_range: bool,
+
+    #[serde(rename = "AnimationRange", default)]
+    pub animation_range: [f32; 2],
+
+    #[serde(rename = "SnapToFrames", default)]
+    pub snap_to_frames: bool,
+
+    #[serde(skip)]
+    pub ping_pong_reverse: bool,
+
+    #[serde(rename = "TimelineOffset", default)]
+    pub timeline_offset: f32,
+
+    #[serde(rename = "TimelineSpeed", default = "default_speed")]
+    pub timeline_speed: f32,
+
+    #[serde(rename = "TimelineEnd", default)]
+    pub timeline_end: TimelineEnd,
+
+    #[serde(rename = "ShowDopesheet", default)]
+    pub show_dopesheet: bool,
+
+    #[serde(rename = "ShowCurves", default)]
+    pub show_curves: bool,
+
+    #[serde(rename = "ShowDiagnostics", default)]
+    pub show_diagnostics: bool,
+
+    #[serde(rename = "ShowHierarchy", default)]
+    pub show_hierarchy: bool,
+
+    #[serde(rename = "ShowValidation", default)]
+    pub show_validation: bool,
+
+    #[serde(rename = "PosePresets", default)]
+    pub pose_presets: BTreeMap<String, BTreeMap<String, JointOffsetJson>>,
+
+    #[serde(rename = "OnionSkin", default)]
+    pub onion_skin: OnionSkinJson,
+
+    #[serde(rename = "MotionTrails", default)]
+    pub motion_trails: MotionTrailsJson,
+
+    #[serde(rename = "JointAxes", default)]
+    pub joint_axes: JointAxesJson,
+
+    #[serde(rename = "HeatmapMode", default)]
+    pub heatmap_mode: HeatmapMode,
+
+    #[serde(rename = "RenderMode", default)]
+    pub render_mode: RenderMode,
+
+    #[serde(rename = "WireframeOverlay", default)]
+    pub wireframe_overlay: WireframeOverlayJson,
+
+    #[serde(rename = "VertexVectors", default)]
+    pub vertex_vectors: VertexVectorsJson,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize, Serialize)]
+pub enum PlaybackMode {
+    #[default]
+    Forward,
+    Reverse,
+    PingPong,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize, Serialize)]
+pub enum TimelineEnd {
+    #[default]
+    Hold,
+    Loop,
+    Stop,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize, Serialize)]
+pub enum RenderMode {
+    #[default]
+    Textured,
+    Normals,
+    UvChecker,
+    Matcap,
+    Depth,
+    SubmeshId,
+    JointIndex,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize, Serialize)]
+pub enum HeatmapMode {
+    #[default]
+    Off,
+    JointWeight,
+    InfluenceCount,
+    WeightSum,
 }
 
 impl OptionsJson {
@@ -76,10 +191,235 @@ impl OptionsJson {
             animation_time: 0.0f32,
             animation_speed: 1.0f32,
             selected_animation_path: String::new(),
+            animation_layers: vec![],
+            retarget: None,
+            lock_root_motion: false,
+            accumulate_root_motion: false,
+            show_root_path: false,
+            playback_mode: PlaybackMode::Forward,
+            use_animation_range: false,
+            animation_range: [0.0f32, 0.0f32],
+            snap_to_frames: false,
+            ping_pong_reverse: false,
+            timeline_offset: 0.0f32,
+            timeline_speed: 1.0f32,
+            timeline_end: TimelineEnd::Hold,
+            show_dopesheet: false,
+            show_curves: false,
+            show_diagnostics: false,
+            show_hierarchy: false,
+            show_validation: false,
+            pose_presets: BTreeMap::new(),
+            onion_skin: OnionSkinJson::default(),
+            motion_trails: MotionTrailsJson::default(),
+            joint_axes: JointAxesJson::default(),
+            heatmap_mode: HeatmapMode::Off,
+            render_mode: RenderMode::Textured,
+            wireframe_overlay: WireframeOverlayJson::default(),
+            vertex_vectors: VertexVectorsJson::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct OnionSkinJson {
+    #[serde(rename = "Show")]
+    pub show: bool,
+
+    #[serde(rename = "BonesOnly")]
+    pub bones_only: bool,
+
+    #[serde(rename = "Frames")]
+    pub frames: usize,
+
+    #[serde(rename = "Step")]
+    pub step: usize,
+
+    #[serde(rename = "BeforeColor")]
+    pub before_color: [f32; 4],
+
+    #[serde(rename = "AfterColor")]
+    pub after_color: [f32; 4],
+}
+
+impl Default for OnionSkinJson {
+    fn default() -> OnionSkinJson {
+        OnionSkinJson {
+            show: false,
+            bones_only: false,
+            frames: 3,
+            step: 2,
+            before_color: [1.0f32, 0.3f32, 0.3f32, 0.5f32],
+            after_color: [0.3f32, 1.0f32, 0.3f32, 0.5f32],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MotionTrailsJson {
+    #[serde(rename = "Show")]
+    pub show: bool,
+
+    #[serde(rename = "Joints")]
+    pub joints: Vec<String>,
+
+    #[serde(rename = "WholeClip")]
+    pub whole_clip: bool,
+
+    #[serde(rename = "Window")]
+    pub window: f32,
+
+    #[serde(rename = "Width")]
+    pub width: f32,
+}
+
+impl Default for MotionTrailsJson {
+    fn default() -> MotionTrailsJson {
+        MotionTrailsJson {
+            show: false,
+            joints: vec![],
+            whole_clip: true,
+            window: 0.5f32,
+            width: 2.0f32,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct WireframeOverlayJson {
+    #[serde(rename = "Show")]
+    pub show: bool,
+
+    #[serde(rename = "Color")]
+    pub color: [f32; 4],
+
+    #[serde(rename = "Width")]
+    pub width: f32,
+
+    #[serde(rename = "DepthOffset")]
+    pub depth_offset: f32,
+
+    #[serde(rename = "ShowVertices")]
+    pub show_vertices: bool,
+
+    #[serde(rename = "VertexColor")]
+    pub vertex_color: [f32; 4],
+
+    #[serde(rename = "VertexSize")]
+    pub vertex_size: f32,
+}
+
+impl Default for WireframeOverlayJson {
+    fn default() -> WireframeOverlayJson {
+        WireframeOverlayJson {
+            show: false,
+            color: [0.0f32, 0.0f32, 0.0f32, 0.6f32],
+            width: 1.0f32,
+            depth_offset: 1.0f32,
+            show_vertices: false,
+            vertex_color: [1.0f32, 0.8f32, 0.0f32, 1.0f32],
+            vertex_size: 3.0f32,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct VertexVectorsJson {
+    #[serde(rename = "ShowNormals")]
+    pub show_normals: bool,
+
+    #[serde(rename = "ShowTangents")]
+    pub show_tangents: bool,
+
+    #[serde(rename = "Length")]
+    pub length: f32,
+
+    #[serde(rename = "ExcludedMeshes")]
+    pub excluded_meshes: Vec<String>,
+}
+
+impl Default for VertexVectorsJson {
+    fn default() -> VertexVectorsJson {
+        VertexVectorsJson {
+            show_normals: false,
+            show_tangents: false,
+            length: 2.0f32,
+            excluded_meshes: vec![],
         }
     }
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct JointAxesJson {
+    #[serde(rename = "Show")]
+    pub show: bool,
+
+    #[serde(rename = "BindPose")]
+    pub bind_pose: bool,
+
+    #[serde(rename = "Size")]
+    pub size: f32,
+
+    #[serde(rename = "Filter")]
+    pub filter: String,
+
+    #[serde(rename = "SelectedSubtree")]
+    pub selected_subtree: bool,
+}
+
+impl Default for JointAxesJson {
+    fn default() -> JointAxesJson {
+        JointAxesJson {
+            show: false,
+            bind_pose: false,
+            size: 5.0f32,
+            filter: String::new(),
+            selected_subtree: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct JointOffsetJson {
+    #[serde(rename = "Translation")]
+    pub translation: [f32; 3],
+
+    #[serde(rename = "Rotation")]
+    pub rotation: [f32; 4],
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LayerJson {
+    #[serde(rename = "Animation")]
+    pub animation: String,
+
+    #[serde(rename = "Weight")]
+    pub weight: f32,
+
+    #[serde(rename = "Additive")]
+    pub additive: bool,
+
+    #[serde(rename = "MaskJoint")]
+    pub mask_joint: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RetargetJson {
+    #[serde(rename = "SourceSkeleton")]
+    pub source_skeleton: String,
+
+    #[serde(rename = "SourceAnimations")]
+    pub source_animations: String,
+
+    #[serde(rename = "Mapping")]
+    pub mapping: BTreeMap<String, String>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct MeshJson {
     #[serde(rename = "Show")]
@@ -89,6 +429,64 @@ pub struct MeshJson {
     pub name_texture: BTreeMap<String, String>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize, Serialize)]
+pub enum ShadingMode {
+    #[default]
+    Unlit,
+    Lambert,
+    BlinnPhong,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LightingJson {
+    #[serde(rename = "Shading")]
+    pub shading: ShadingMode,
+
+    #[serde(rename = "LightYaw")]
+    pub light_yaw: f32,
+
+    #[serde(rename = "LightPitch")]
+    pub light_pitch: f32,
+
+    #[serde(rename = "LightColor")]
+    pub light_color: [f32; 3],
+
+    #[serde(rename = "Ambient")]
+    pub ambient: f32,
+
+    #[serde(rename = "Specular")]
+    pub specular: f32,
+
+    #[serde(rename = "Shininess")]
+    pub shininess: f32,
+}
+
+impl Default for LightingJson {
+    fn default() -> LightingJson {
+        LightingJson {
+            shading: ShadingMode::Unlit,
+            light_yaw: 45.0f32,
+            light_pitch: 45.0f32,
+            light_color: [1.0f32, 1.0f32, 1.0f32],
+            ambient: 0.3f32,
+            specular: 0.5f32,
+            shininess: 32.0f32,
+        }
+    }
+}
+
+impl LightingJson {
+    pub fn direction(&self) -> glam::Vec3 {
+        let (yaw, pitch) = (self.light_yaw.to_radians(), self.light_pitch.to_radians());
+        glam::vec3(
+            pitch.cos() * yaw.sin(),
+            pitch.sin(),
+            pitch.cos() * yaw.cos(),
+        )
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ConfigJson {
     #[serde(rename = "MSAA")]
@@ -106,6 +504,21 @@ pub struct ConfigJson {
     #[serde(rename = "SynchronizedTime")]
     pub synchronized_time: bool,
 
+    #[serde(rename = "TimelineTime", default)]
+    pub timeline_time: f32,
+
+    #[serde(rename = "TimelinePlaying", default)]
+    pub timeline_playing: bool,
+
+    #[serde(rename = "TimelineLoop", default)]
+    pub timeline_loop: bool,
+
+    #[serde(rename = "TimelineSpeed", default = "default_speed")]
+    pub timeline_speed: f32,
+
+    #[serde(rename = "Lighting", default)]
+    pub lighting: LightingJson,
+
     #[serde(rename = "ScreenShotResolution")]
     pub screen_shot_resolution: [GLsizei; 2],
 
@@ -160,6 +573,11 @@ impl ConfigJson {
             config_json.meshes.extend_from_slice(&meshes);
         }
 
+        config_json.timeline_speed = config_json.timeline_speed.max(0.01f32);
+        for options in config_json.options.iter_mut() {
+            options.timeline_speed = options.timeline_speed.max(0.01f32);
+        }
+
         println!("Finished reading config file");
 
         config_json
@@ -175,9 +593,41 @@ impl ConfigJson {
             .iter_mut()
             .enumerate()
             .for_each(|(i, config)| {
-                config.selected_animation_path = mind_models[i].animations_file_names
-                    [mind_models[i].animation_selected]
-                    .to_owned()
+                let mind_model = &mind_models[i];
+
+                config.selected_animation_path =
+                    mind_model.animations_file_names[mind_model.animation_selected].to_owned();
+
+                config.animation_layers = mind_model
+                    .animation_layers
+                    .iter()
+                    .map(|layer| LayerJson {
+                        animation: mind_model.animations_file_names[layer.animation].to_owned(),
+                        weight: layer.weight,
+                        additive: layer.additive,
+                        mask_joint: layer
+                            .mask
+                            .map(|mask| mind_model.skeleton.joints[mask].name.to_owned()),
+                    })
+                    .collect();
+
+                config.retarget = mind_model.retarget.as_ref().map(|retarget| RetargetJson {
+                    source_skeleton: retarget.skeleton_path.to_owned(),
+                    source_animations: retarget.animations_path.to_owned(),
+                    mapping: retarget
+                        .mapping
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(target_index, source_index)| {
+                            source_index.map(|source_index| {
+                                (
+                                    mind_model.skeleton.joints[target_index].name.to_owned(),
+                                    retarget.skeleton.joints[source_index].name.to_owned(),
+                                )
+                            })
+                        })
+                        .collect(),
+                });
             });
 
         config_json.meshes = Vec::with_capacity(config_json.paths.len());
@@ -230,6 +680,11 @@ impl ConfigJson {
             show_floor: true,
             show_skybox: true,
             synchronized_time: false,
+            timeline_time: 0.0f32,
+            timeline_playing: false,
+            timeline_loop: true,
+            timeline_speed: 1.0f32,
+            lighting: LightingJson::default(),
             screen_shot_resolution: [1920, 1080],
             paths: vec![],
             options: vec![],
@@ -238,6 +693,10 @@ impl ConfigJson {
     }
 }
 
+fn default_speed() -> f32 {
+    1.0f32
+}
+
 fn pretty_json(config_json: &ConfigJson) -> Result<String, serde_json::Error> {
     let mut buffer = Vec::new();
     let formatter = serde_json::ser::PrettyFormatter::with_indent(b"\t");
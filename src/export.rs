@@ -24,11 +24,46 @@ use gltf::{
 
 use crate::{
     gls::glam_read,
-    lol::{anm, Animation, Skeleton, Skin},
+    lol::{
+        anm::{self, JointPose},
+        posing, Animation, Skeleton, Skin,
+    },
     MindModel,
 };
 
 pub fn export_model(export_as: u8, model_name: &String, mind_model: &MindModel) {
+    let animations: Vec<(&Animation, &String)> = mind_model
+        .animations
+        .iter()
+        .zip(mind_model.animations_file_names.iter())
+        .collect();
+    export(export_as, model_name, mind_model, &animations, None);
+}
+
+pub fn export_pose(
+    export_as: u8,
+    model_name: &String,
+    mind_model: &MindModel,
+    pose_name: &String,
+    pose: &[JointPose],
+) {
+    let animation = posing::pose_animation(pose, &mind_model.skeleton);
+    export(
+        export_as,
+        &format!("{model_name}_{pose_name}"),
+        mind_model,
+        &[(&animation, pose_name)],
+        Some(pose),
+    );
+}
+
+fn export(
+    export_as: u8,
+    model_name: &String,
+    mind_model: &MindModel,
+    animations: &[(&Animation, &String)],
+    pose: Option<&[JointPose]>,
+) {
     let export_path = format!("export/{model_name}");
     if export_as == 0 {
         fs::create_dir_all(&export_path).expect("Could not create export dirs");
@@ -74,6 +109,7 @@ pub fn export_model(export_as: u8, model_name: &String, mind_model: &MindModel)
     let (nodes, gltf_skin, ibm_data, ibm_buffer_view, ibm_accessor) = make_skeleton(
         model_name,
         &mind_model.skeleton,
+        pose,
         &mut accessor_index,
         &mut buffer_view_index,
         &mut buffer_view_offset,
@@ -86,12 +122,12 @@ pub fn export_model(export_as: u8, model_name: &String, mind_model: &MindModel)
 
     let mut animations_gltf = vec![];
 
-    for i in 0..mind_model.animations.len() {
+    for (animation, animation_file_name) in animations.iter() {
         let (animation_gltf, animation_data, animation_buffer_view, animation_accessor) =
             make_animation(
                 &mind_model.skeleton,
-                &mind_model.animations[i],
-                &mind_model.animations_file_names[i],
+                animation,
+                animation_file_name,
                 &mut accessor_index,
                 &mut buffer_view_index,
                 &mut buffer_view_offset,
@@ -655,6 +691,7 @@ fn make_primitives(
 fn make_skeleton(
     model_name: &String,
     skeleton: &Skeleton,
+    pose: Option<&[JointPose]>,
     accessor_index: &mut u32,
     buffer_view_index: &mut u32,
     buffer_view_offset: &mut usize,
@@ -662,9 +699,12 @@ fn make_skeleton(
     let mut nodes = vec![];
 
     for i in 0..skeleton.joints.len() {
-        let (scale, rotation, translation) = skeleton.joints[i]
-            .local_matrix
-            .to_scale_rotation_translation();
+        let (scale, rotation, translation) = match pose {
+            Some(pose) => (pose[i].scale, pose[i].rotation, pose[i].translation),
+            None => skeleton.joints[i]
+                .local_matrix
+                .to_scale_rotation_translation(),
+        };
         let children = if !skeleton.joints[i].children.is_empty() {
             Some(
                 skeleton.joints[i]
@@ -8,6 +8,8 @@ pub struct Bones {
     bo: Vec<GLuint>,
     shader: Rc<Shader>,
     mvp_ref: GLint,
+    use_ghost_ref: GLint,
+    ghost_color_ref: GLint,
     bones: Vec<glam::Vec4>,
     bones_tpose: *const glam::Vec4,
 }
@@ -67,6 +69,8 @@ impl Bones {
                 bo,
                 shader,
                 mvp_ref: 0,
+                use_ghost_ref: 0,
+                ghost_color_ref: 0,
                 bones,
                 bones_tpose,
             }
@@ -80,23 +84,7 @@ impl Bones {
         mind_model: &MindModel,
     ) {
         let bones_ptr = if use_animation {
-            let mut line_index: usize = 0;
-
-            for i in 0..mind_model.skeleton.joints.len() {
-                let parent_id = mind_model.skeleton.joints[i].parent_id;
-
-                if parent_id != -1 {
-                    self.bones[line_index] = mind_model.joints_transforms[parent_id as usize]
-                        * mind_model.skeleton.joints[parent_id as usize].global_matrix
-                        * glam::Vec4::ONE;
-
-                    self.bones[line_index + 1] = mind_model.joints_transforms[i]
-                        * mind_model.skeleton.joints[i].global_matrix
-                        * glam::Vec4::ONE;
-
-                    line_index += 2;
-                }
-            }
+            self.update_bones(&mind_model.joints_transforms, mind_model);
             self.bones.as_ptr()
         } else {
             self.bones_tpose
@@ -113,25 +101,88 @@ impl Bones {
                 gl::FALSE,
                 projection_view_matrix.as_ref() as *const GLfloat,
             );
+            gl::Uniform1i(self.use_ghost_ref, 0);
 
-            gl::BindVertexArray(self.vao);
+            self.draw_bones(bones_ptr);
+        }
+    }
 
-            gl::BindBuffer(gl::ARRAY_BUFFER, self.bo[0]);
-            gl::BufferSubData(
-                gl::ARRAY_BUFFER,
-                0,
-                (self.bones.len() * mem::size_of::<glam::Vec4>()) as GLsizeiptr,
-                bones_ptr as *const c_void,
+    pub fn render_ghost(
+        &mut self,
+        use_samples: bool,
+        projection_view_matrix: &glam::Mat4,
+        joints_transforms: &[glam::Mat4],
+        mind_model: &MindModel,
+        color: &[f32; 4],
+    ) {
+        self.update_bones(joints_transforms, mind_model);
+
+        unsafe {
+            gl::Disable(gl::DEPTH_TEST);
+            gl::Enable(gl::BLEND);
+            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+            gl::LineWidth(2.0f32);
+
+            self.shader.as_ref().enable();
+            gl::UniformMatrix4fv(
+                self.mvp_ref,
+                1,
+                gl::FALSE,
+                projection_view_matrix.as_ref() as *const GLfloat,
             );
+            gl::Uniform1i(self.use_ghost_ref, 1);
+            gl::Uniform4fv(self.ghost_color_ref, 1, color.as_ptr());
 
-            gl::DrawArrays(gl::LINES, 0, self.bones.len() as GLsizei);
+            self.draw_bones(self.bones.as_ptr());
 
-            gl::BindVertexArray(0);
+            gl::Uniform1i(self.use_ghost_ref, 0);
+
+            if !use_samples {
+                gl::Disable(gl::BLEND);
+            }
+        }
+    }
+
+    fn update_bones(&mut self, joints_transforms: &[glam::Mat4], mind_model: &MindModel) {
+        let mut line_index: usize = 0;
+
+        for i in 0..mind_model.skeleton.joints.len() {
+            let parent_id = mind_model.skeleton.joints[i].parent_id;
+
+            if parent_id != -1 {
+                self.bones[line_index] = joints_transforms[parent_id as usize]
+                    * mind_model.skeleton.joints[parent_id as usize].global_matrix
+                    * glam::Vec4::ONE;
+
+                self.bones[line_index + 1] = joints_transforms[i]
+                    * mind_model.skeleton.joints[i].global_matrix
+                    * glam::Vec4::ONE;
+
+                line_index += 2;
+            }
         }
     }
 
+    unsafe fn draw_bones(&self, bones_ptr: *const glam::Vec4) {
+        gl::BindVertexArray(self.vao);
+
+        gl::BindBuffer(gl::ARRAY_BUFFER, self.bo[0]);
+        gl::BufferSubData(
+            gl::ARRAY_BUFFER,
+            0,
+            (self.bones.len() * mem::size_of::<glam::Vec4>()) as GLsizeiptr,
+            bones_ptr as *const c_void,
+        );
+
+        gl::DrawArrays(gl::LINES, 0, self.bones.len() as GLsizei);
+
+        gl::BindVertexArray(0);
+    }
+
     pub fn set_shader_refs(&mut self, refs: &[GLint]) {
         self.mvp_ref = refs[0];
+        self.use_ghost_ref = refs[1];
+        self.ghost_color_ref = refs[2];
     }
 }
 
@@ -0,0 +1,119 @@
+use gl::types::{GLenum, GLfloat, GLint, GLsizei, GLsizeiptr, GLuint};
+use std::{mem, os::raw::c_void, ptr, rc::Rc};
+
+use crate::gls::Shader;
+
+pub struct Lines {
+    vao: GLuint,
+    bo: Vec<GLuint>,
+    shader: Rc<Shader>,
+    mvp_ref: GLint,
+    count: usize,
+}
+
+impl Lines {
+    pub fn create(shader: Rc<Shader>) -> Lines {
+        unsafe {
+            let mut vao: GLuint = 0;
+            let mut bo: Vec<GLuint> = vec![0; 2];
+
+            gl::GenVertexArrays(1, &mut vao);
+            gl::GenBuffers(2, bo.as_mut_ptr());
+
+            gl::BindVertexArray(vao);
+
+            gl::BindBuffer(gl::ARRAY_BUFFER, bo[0]);
+            gl::EnableVertexAttribArray(0);
+            gl::VertexAttribPointer(0, 4, gl::FLOAT, gl::FALSE, 0, ptr::null());
+
+            gl::BindBuffer(gl::ARRAY_BUFFER, bo[1]);
+            gl::EnableVertexAttribArray(1);
+            gl::VertexAttribPointer(1, 3, gl::FLOAT, gl::FALSE, 0, ptr::null());
+
+            gl::BindVertexArray(0);
+
+            Lines {
+                vao,
+                bo,
+                shader,
+                mvp_ref: 0,
+                count: 0,
+            }
+        }
+    }
+
+    pub fn update(&mut self, positions: &[glam::Vec4], colors: &[glam::Vec3]) {
+        self.count = positions.len().min(colors.len());
+
+        unsafe {
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.bo[0]);
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                (self.count * mem::size_of::<glam::Vec4>()) as GLsizeiptr,
+                positions.as_ptr() as *const c_void,
+                gl::DYNAMIC_DRAW,
+            );
+
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.bo[1]);
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                (self.count * mem::size_of::<glam::Vec3>()) as GLsizeiptr,
+                colors.as_ptr() as *const c_void,
+                gl::DYNAMIC_DRAW,
+            );
+
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+        }
+    }
+
+    pub fn render(
+        &self,
+        mode: GLenum,
+        line_width: f32,
+        depth_test: bool,
+        projection_view_matrix: &glam::Mat4,
+    ) {
+        if self.count == 0 {
+            return;
+        }
+
+        unsafe {
+            if depth_test {
+                gl::Enable(gl::DEPTH_TEST);
+                gl::DepthFunc(gl::LEQUAL);
+            } else {
+                gl::Disable(gl::DEPTH_TEST);
+            }
+            gl::LineWidth(line_width);
+
+            self.shader.as_ref().enable();
+            gl::UniformMatrix4fv(
+                self.mvp_ref,
+                1,
+                gl::FALSE,
+                projection_view_matrix.as_ref() as *const GLfloat,
+            );
+
+            gl::BindVertexArray(self.vao);
+
+            gl::DrawArrays(mode, 0, self.count as GLsizei);
+
+            gl::BindVertexArray(0);
+
+            gl::DepthFunc(gl::LESS);
+        }
+    }
+
+    pub fn set_shader_refs(&mut self, refs: &[GLint]) {
+        self.mvp_ref = refs[0];
+    }
+}
+
+impl Drop for Lines {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteBuffers(2, self.bo.as_ptr());
+            gl::DeleteVertexArrays(1, &self.vao);
+        }
+    }
+}
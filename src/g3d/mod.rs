@@ -1,6 +1,7 @@
 pub mod bones;
 pub mod floor;
 pub mod joints;
+pub mod lines;
 pub mod model;
 pub mod names;
 pub mod skybox;
@@ -9,6 +10,7 @@ pub mod refs_shaders;
 pub use self::bones::Bones;
 pub use self::floor::Floor;
 pub use self::joints::Joints;
+pub use self::lines::Lines;
 pub use self::model::Model;
 pub use self::names::Names;
 pub use self::skybox::Skybox;
@@ -19,4 +21,10 @@ pub struct Character {
     pub names: Names,
     pub bones: Bones,
     pub joints: Joints,
+    pub root_path: Lines,
+    pub highlight: Lines,
+    pub trails: Lines,
+    pub axes: Lines,
+    pub outline: Lines,
+    pub vectors: Lines,
 }
@@ -2,7 +2,7 @@ use gl::types::{GLfloat, GLint, GLsizei, GLsizeiptr, GLuint};
 use std::{mem, os::raw::c_void, ptr, rc::Rc};
 
 use crate::{
-    config_json,
+    config_json::{HeatmapMode, LightingJson, RenderMode, WireframeOverlayJson},
     gls::{glam_read, Shader},
     lol::Skin,
     MindModel,
@@ -14,16 +14,42 @@ pub struct Model {
     shader: Rc<Shader>,
     mvp_ref: GLint,
     use_joint_ref: GLint,
+    use_ghost_ref: GLint,
+    ghost_color_ref: GLint,
+    heatmap_mode_ref: GLint,
+    heatmap_joint_ref: GLint,
+    shading_mode_ref: GLint,
+    light_direction_ref: GLint,
+    light_color_ref: GLint,
+    ambient_ref: GLint,
+    specular_ref: GLint,
+    shininess_ref: GLint,
+    camera_position_ref: GLint,
+    model_view_ref: GLint,
+    render_mode_ref: GLint,
+    submesh_color_ref: GLint,
+    depth_range_ref: GLint,
+    use_overlay_ref: GLint,
+    overlay_color_ref: GLint,
+}
+
+pub fn id_color(id: u32) -> [f32; 3] {
+    let hash = (id + 1).wrapping_mul(2654435761);
+    [
+        ((hash >> 24) & 255) as f32 / 255.0f32,
+        ((hash >> 16) & 255) as f32 / 255.0f32,
+        ((hash >> 8) & 255) as f32 / 255.0f32,
+    ]
 }
 
 impl Model {
     pub fn create(skin: &Skin, shader: Rc<Shader>) -> Model {
         unsafe {
             let mut vao: GLuint = 0;
-            let mut bo: Vec<GLuint> = vec![0; 6];
+            let mut bo: Vec<GLuint> = vec![0; 7];
 
             gl::GenVertexArrays(1, &mut vao);
-            gl::GenBuffers(6, bo.as_mut_ptr());
+            gl::GenBuffers(7, bo.as_mut_ptr());
 
             gl::BindVertexArray(vao);
 
@@ -71,6 +97,17 @@ impl Model {
             gl::EnableVertexAttribArray(3);
             gl::VertexAttribPointer(3, 4, gl::FLOAT, gl::FALSE, 0, ptr::null());
 
+            gl::BindBuffer(gl::ARRAY_BUFFER, bo[6]);
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                (skin.normals.len() * mem::size_of::<glam::Vec3>()) as GLsizeiptr,
+                skin.normals.as_ptr() as *const c_void,
+                gl::STATIC_DRAW,
+            );
+
+            gl::EnableVertexAttribArray(4);
+            gl::VertexAttribPointer(4, 3, gl::FLOAT, gl::FALSE, 0, ptr::null());
+
             gl::BindBuffer(gl::UNIFORM_BUFFER, bo[4]);
             gl::BufferData(
                 gl::UNIFORM_BUFFER,
@@ -95,13 +132,37 @@ impl Model {
                 shader,
                 mvp_ref: 0,
                 use_joint_ref: 0,
+                use_ghost_ref: 0,
+                ghost_color_ref: 0,
+                heatmap_mode_ref: 0,
+                heatmap_joint_ref: 0,
+                shading_mode_ref: 0,
+                light_direction_ref: 0,
+                light_color_ref: 0,
+                ambient_ref: 0,
+                specular_ref: 0,
+                shininess_ref: 0,
+                camera_position_ref: 0,
+                model_view_ref: 0,
+                render_mode_ref: 0,
+                submesh_color_ref: 0,
+                depth_range_ref: 0,
+                use_overlay_ref: 0,
+                overlay_color_ref: 0,
             }
         }
     }
 
     pub fn render(
         &self,
-        options: &config_json::OptionsJson,
+        show_wireframe: bool,
+        use_animation: bool,
+        render_mode: RenderMode,
+        heatmap_mode: HeatmapMode,
+        lighting: &LightingJson,
+        light_direction: glam::Vec3,
+        camera_position: glam::Vec3,
+        model_view_matrix: &glam::Mat4,
         projection_view_matrix: &glam::Mat4,
         mind_model: &MindModel,
     ) {
@@ -123,52 +184,61 @@ impl Model {
                 gl::FALSE,
                 projection_view_matrix.as_ref() as *const GLfloat,
             );
+            gl::Uniform1i(self.use_ghost_ref, 0);
+            gl::Uniform1i(self.use_overlay_ref, 0);
+            gl::Uniform1i(self.heatmap_mode_ref, heatmap_mode as GLint);
+            gl::Uniform1i(
+                self.heatmap_joint_ref,
+                mind_model.selected_joint.map_or(-1, |joint| joint as GLint),
+            );
 
-            if options.use_animation {
-                gl::Uniform1i(self.use_joint_ref, 1);
+            gl::Uniform1i(self.shading_mode_ref, lighting.shading as GLint);
+            gl::Uniform3fv(
+                self.light_direction_ref,
+                1,
+                light_direction.as_ref().as_ptr(),
+            );
+            gl::Uniform3fv(self.light_color_ref, 1, lighting.light_color.as_ptr());
+            gl::Uniform1f(self.ambient_ref, lighting.ambient);
+            gl::Uniform1f(self.specular_ref, lighting.specular);
+            gl::Uniform1f(self.shininess_ref, lighting.shininess);
+            gl::Uniform3fv(
+                self.camera_position_ref,
+                1,
+                camera_position.as_ref().as_ptr(),
+            );
 
-                gl::BindBuffer(gl::UNIFORM_BUFFER, self.bo[4]);
-                gl::BufferSubData(
-                    gl::UNIFORM_BUFFER,
-                    0,
-                    (mind_model.joints_transforms.len() * mem::size_of::<glam::Mat4>())
-                        as GLsizeiptr,
-                    mind_model.joints_transforms.as_ptr() as *const c_void,
-                );
-                gl::BindBufferRange(
-                    gl::UNIFORM_BUFFER,
-                    0,
-                    self.bo[4],
-                    0,
-                    (mind_model.joints_transforms.len() * mem::size_of::<glam::Mat4>())
-                        as GLsizeiptr,
-                );
+            gl::UniformMatrix4fv(
+                self.model_view_ref,
+                1,
+                gl::FALSE,
+                model_view_matrix.as_ref() as *const GLfloat,
+            );
+            gl::Uniform1i(self.render_mode_ref, render_mode as GLint);
+
+            let [bbmin, bbmax] = mind_model.skin.bounding_box;
+            let radius = bbmin.distance(bbmax) / 2.0f32;
+            let distance = camera_position.distance(mind_model.skin.center);
+            gl::Uniform2f(
+                self.depth_range_ref,
+                (distance - radius).max(0.0f32),
+                distance + radius,
+            );
+
+            if use_animation {
+                gl::Uniform1i(self.use_joint_ref, 1);
+                self.upload_joints_transforms(&mind_model.joints_transforms);
             } else {
                 gl::Uniform1i(self.use_joint_ref, 0);
             }
 
-            if options.show_wireframe {
+            if show_wireframe {
                 gl::PolygonMode(gl::FRONT_AND_BACK, gl::LINE);
             }
 
-            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, self.bo[5]);
-
-            for i in 0..mind_model.skin.meshes.len() {
-                if mind_model.show_meshes[i] {
-                    mind_model.textures[mind_model.textures_selecteds[i]].bind();
-
-                    gl::DrawElements(
-                        gl::TRIANGLES,
-                        mind_model.skin.meshes[i].submesh.indices_count as GLsizei,
-                        gl::UNSIGNED_SHORT,
-                        (mind_model.skin.meshes[i].submesh.indices_offset
-                            * mem::size_of::<u16>() as u32)
-                            as *const c_void,
-                    );
-                }
-            }
+            self.draw_meshes(mind_model);
 
-            if options.show_wireframe {
+            if show_wireframe {
                 gl::PolygonMode(gl::FRONT_AND_BACK, gl::FILL);
             }
 
@@ -176,10 +246,191 @@ impl Model {
         }
     }
 
+    pub fn render_ghost(
+        &self,
+        use_samples: bool,
+        projection_view_matrix: &glam::Mat4,
+        joints_transforms: &[glam::Mat4],
+        mind_model: &MindModel,
+        color: &[f32; 4],
+    ) {
+        unsafe {
+            let last_enable_cull_face = gl::IsEnabled(gl::CULL_FACE) == gl::TRUE;
+            gl::Disable(gl::CULL_FACE);
+            gl::Enable(gl::DEPTH_TEST);
+            gl::DepthFunc(gl::LEQUAL);
+            gl::DepthMask(gl::FALSE);
+            gl::Enable(gl::BLEND);
+            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+
+            self.shader.as_ref().enable();
+
+            gl::ActiveTexture(gl::TEXTURE0);
+
+            gl::BindVertexArray(self.vao);
+
+            gl::UniformMatrix4fv(
+                self.mvp_ref,
+                1,
+                gl::FALSE,
+                projection_view_matrix.as_ref() as *const GLfloat,
+            );
+            gl::Uniform1i(self.use_ghost_ref, 1);
+            gl::Uniform4fv(self.ghost_color_ref, 1, color.as_ptr());
+            gl::Uniform1i(self.heatmap_mode_ref, HeatmapMode::Off as GLint);
+            gl::Uniform1i(self.render_mode_ref, RenderMode::Textured as GLint);
+            gl::Uniform1i(self.use_joint_ref, 1);
+            self.upload_joints_transforms(joints_transforms);
+
+            self.draw_meshes(mind_model);
+
+            gl::Uniform1i(self.use_ghost_ref, 0);
+
+            gl::BindVertexArray(0);
+
+            gl::DepthMask(gl::TRUE);
+            gl::DepthFunc(gl::LESS);
+            if !use_samples {
+                gl::Disable(gl::BLEND);
+            }
+            if last_enable_cull_face {
+                gl::Enable(gl::CULL_FACE);
+            }
+        }
+    }
+
+    pub fn render_overlay(
+        &self,
+        use_animation: bool,
+        use_samples: bool,
+        overlay: &WireframeOverlayJson,
+        projection_view_matrix: &glam::Mat4,
+        mind_model: &MindModel,
+    ) {
+        unsafe {
+            let last_enable_cull_face = gl::IsEnabled(gl::CULL_FACE) == gl::TRUE;
+            gl::Disable(gl::CULL_FACE);
+            gl::Enable(gl::DEPTH_TEST);
+            gl::DepthFunc(gl::LEQUAL);
+            gl::DepthMask(gl::FALSE);
+            gl::Enable(gl::BLEND);
+            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+            gl::Enable(gl::POLYGON_OFFSET_LINE);
+            gl::Enable(gl::POLYGON_OFFSET_POINT);
+            gl::PolygonOffset(-overlay.depth_offset, -overlay.depth_offset);
+
+            self.shader.as_ref().enable();
+
+            gl::BindVertexArray(self.vao);
+
+            gl::UniformMatrix4fv(
+                self.mvp_ref,
+                1,
+                gl::FALSE,
+                projection_view_matrix.as_ref() as *const GLfloat,
+            );
+            gl::Uniform1i(self.use_ghost_ref, 0);
+            gl::Uniform1i(self.use_overlay_ref, 1);
+
+            if use_animation {
+                gl::Uniform1i(self.use_joint_ref, 1);
+                self.upload_joints_transforms(&mind_model.joints_transforms);
+            } else {
+                gl::Uniform1i(self.use_joint_ref, 0);
+            }
+
+            if overlay.show {
+                gl::LineWidth(overlay.width);
+                gl::PolygonMode(gl::FRONT_AND_BACK, gl::LINE);
+                gl::Uniform4fv(self.overlay_color_ref, 1, overlay.color.as_ptr());
+                self.draw_meshes(mind_model);
+            }
+
+            if overlay.show_vertices {
+                gl::PointSize(overlay.vertex_size);
+                gl::PolygonMode(gl::FRONT_AND_BACK, gl::POINT);
+                gl::Uniform4fv(self.overlay_color_ref, 1, overlay.vertex_color.as_ptr());
+                self.draw_meshes(mind_model);
+            }
+
+            gl::PolygonMode(gl::FRONT_AND_BACK, gl::FILL);
+            gl::Uniform1i(self.use_overlay_ref, 0);
+
+            gl::BindVertexArray(0);
+
+            gl::LineWidth(1.0f32);
+            gl::PointSize(4.0f32);
+            gl::PolygonOffset(0.0f32, 0.0f32);
+            gl::Disable(gl::POLYGON_OFFSET_LINE);
+            gl::Disable(gl::POLYGON_OFFSET_POINT);
+            gl::DepthMask(gl::TRUE);
+            gl::DepthFunc(gl::LESS);
+            if !use_samples {
+                gl::Disable(gl::BLEND);
+            }
+            if last_enable_cull_face {
+                gl::Enable(gl::CULL_FACE);
+            }
+        }
+    }
+
+    unsafe fn upload_joints_transforms(&self, joints_transforms: &[glam::Mat4]) {
+        gl::BindBuffer(gl::UNIFORM_BUFFER, self.bo[4]);
+        gl::BufferSubData(
+            gl::UNIFORM_BUFFER,
+            0,
+            mem::size_of_val(joints_transforms) as GLsizeiptr,
+            joints_transforms.as_ptr() as *const c_void,
+        );
+        gl::BindBufferRange(
+            gl::UNIFORM_BUFFER,
+            0,
+            self.bo[4],
+            0,
+            mem::size_of_val(joints_transforms) as GLsizeiptr,
+        );
+    }
+
+    unsafe fn draw_meshes(&self, mind_model: &MindModel) {
+        gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, self.bo[5]);
+
+        for i in 0..mind_model.skin.meshes.len() {
+            if mind_model.show_meshes[i] {
+                mind_model.textures[mind_model.textures_selecteds[i]].bind();
+                gl::Uniform3fv(self.submesh_color_ref, 1, id_color(i as u32).as_ptr());
+
+                gl::DrawElements(
+                    gl::TRIANGLES,
+                    mind_model.skin.meshes[i].submesh.indices_count as GLsizei,
+                    gl::UNSIGNED_SHORT,
+                    (mind_model.skin.meshes[i].submesh.indices_offset
+                        * mem::size_of::<u16>() as u32) as *const c_void,
+                );
+            }
+        }
+    }
+
     pub fn set_shader_refs(&mut self, refs: &[GLint], ubo_ref: GLuint) {
         self.mvp_ref = refs[0];
         let diffuse_ref = refs[1];
         self.use_joint_ref = refs[2];
+        self.use_ghost_ref = refs[3];
+        self.ghost_color_ref = refs[4];
+        self.heatmap_mode_ref = refs[5];
+        self.heatmap_joint_ref = refs[6];
+        self.shading_mode_ref = refs[7];
+        self.light_direction_ref = refs[8];
+        self.light_color_ref = refs[9];
+        self.ambient_ref = refs[10];
+        self.specular_ref = refs[11];
+        self.shininess_ref = refs[12];
+        self.camera_position_ref = refs[13];
+        self.model_view_ref = refs[14];
+        self.render_mode_ref = refs[15];
+        self.submesh_color_ref = refs[16];
+        self.depth_range_ref = refs[17];
+        self.use_overlay_ref = refs[18];
+        self.overlay_color_ref = refs[19];
 
         let shader = self.shader.as_ref();
         unsafe {
@@ -193,7 +444,7 @@ impl Model {
 impl Drop for Model {
     fn drop(&mut self) {
         unsafe {
-            gl::DeleteBuffers(6, self.bo.as_ptr());
+            gl::DeleteBuffers(7, self.bo.as_ptr());
             gl::DeleteVertexArrays(1, &self.vao);
         }
     }
@@ -7,6 +7,7 @@ pub struct Shaders {
     pub names: Rc<Shader>,
     pub bones: Rc<Shader>,
     pub joints: Rc<Shader>,
+    pub lines: Rc<Shader>,
 }
 
 impl Shaders {
@@ -23,6 +24,10 @@ impl Shaders {
             include_str!("../../assets/joints/joints.vert"),
             include_str!("../../assets/joints/joints.frag"),
         ));
+        let lines = Rc::new(Shader::create(
+            include_str!("../../assets/lines/lines.vert"),
+            include_str!("../../assets/lines/lines.frag"),
+        ));
         let names = Rc::new(Shader::create(
             include_str!("../../assets/names/names.vert"),
             include_str!("../../assets/names/names.frag"),
@@ -33,6 +38,7 @@ impl Shaders {
             names,
             bones,
             joints,
+            lines,
         }
     }
 }
@@ -42,15 +48,38 @@ pub struct Refs {
     pub names: Vec<GLint>,
     pub bones: Vec<GLint>,
     pub joints: Vec<GLint>,
+    pub lines: Vec<GLint>,
     pub model_ubo: GLuint,
 }
 
 impl Refs {
     pub fn new(shaders: &Shaders) -> Refs {
-        let model = shaders.model.get_refs(&["MVP", "Diffuse", "UseBone"]);
+        let model = shaders.model.get_refs(&[
+            "MVP",
+            "Diffuse",
+            "UseBone",
+            "UseGhost",
+            "GhostColor",
+            "HeatmapMode",
+            "HeatmapJoint",
+            "ShadingMode",
+            "LightDirection",
+            "LightColor",
+            "Ambient",
+            "Specular",
+            "Shininess",
+            "CameraPosition",
+            "ModelView",
+            "RenderMode",
+            "SubmeshColor",
+            "DepthRange",
+            "UseOverlay",
+            "OverlayColor",
+        ]);
         let model_ubo = shaders.model.get_ubo_ref("BonesTransformsBlock");
-        let bones = shaders.bones.get_refs(&["MVP"]);
+        let bones = shaders.bones.get_refs(&["MVP", "UseGhost", "GhostColor"]);
         let joints = shaders.joints.get_refs(&["MVP"]);
+        let lines = shaders.lines.get_refs(&["MVP"]);
         let names = shaders.names.get_refs(&[
             "MVP",
             "TextSize",
@@ -68,6 +97,7 @@ impl Refs {
             names,
             bones,
             joints,
+            lines,
             model_ubo,
         }
     }
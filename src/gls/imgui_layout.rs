@@ -1,10 +1,32 @@
 use glfw::Glfw;
 use native_dialog::FileDialog;
-use std::path::PathBuf;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 use crate::{
-    config_json::{ConfigJson, OptionsJson},
-    export, MindModel,
+    config_json::{
+        ConfigJson, HeatmapMode, JointOffsetJson, OptionsJson, PlaybackMode, RenderMode,
+        ShadingMode, TimelineEnd,
+    },
+    export,
+    g3d::model,
+    lol::{
+        anm::{sample_joint, Animation, JointPose},
+        compare::{CompareMetric, Comparison},
+        diagnostics::{self, AnimationReport},
+        hasher,
+        ik::{self, IkHandle},
+        layer::AnimationLayer,
+        mirror::{self, MirrorAxis, MirrorPairs},
+        picking::{project_to_screen, unproject_from_screen},
+        posing,
+        retarget::MatchBy,
+        skl::joint_position,
+        validation,
+    },
+    MindModel,
 };
 
 pub fn settings(
@@ -40,102 +62,3077 @@ pub fn settings(
     ui.checkbox("Show Floor", &mut config_json.show_floor);
     ui.checkbox("Show Skybox", &mut config_json.show_skybox);
 
-    ui.checkbox("Synchronized Time", &mut config_json.synchronized_time);
+    ui.checkbox("Master Timeline", &mut config_json.synchronized_time);
     if ui.is_item_hovered() {
         ui.tooltip(|| {
-            ui.text("Synchronize all models to first model");
+            ui.text("Drive all models from a shared timeline");
         });
     }
-}
-
-pub fn model(
-    ui: &imgui::Ui,
-    options: &mut OptionsJson,
-    mind_model: &mut MindModel,
-    export_as: &mut u8,
-    name: &String,
-) {
-    ui.checkbox("Show Wireframe", &mut options.show_wireframe);
-    ui.checkbox("Show Skeleton Names", &mut options.show_skeleton_names);
-    ui.checkbox("Show Skeleton Bones", &mut options.show_skeleton_bones);
-    ui.checkbox("Show Skeleton Joints", &mut options.show_skeleton_joints);
 
-    ui.tree_node_config("Animations")
+    ui.tree_node_config("Lighting")
         .flags(imgui::TreeNodeFlags::SPAN_AVAIL_WIDTH)
         .framed(true)
         .build(|| {
-            ui.checkbox("Use Animation", &mut options.use_animation);
-            ui.checkbox("Play / Stop", &mut options.play_animation);
-            ui.checkbox("Loop Animation", &mut options.loop_animation);
-            ui.checkbox("Next Animation", &mut options.next_animation);
+            let lighting = &mut config_json.lighting;
 
-            ui.text("CTRL+Click Change To Input");
+            ui.radio_button("Unlit", &mut lighting.shading, ShadingMode::Unlit);
+            ui.same_line();
+            ui.radio_button("Lambert", &mut lighting.shading, ShadingMode::Lambert);
+            ui.same_line();
+            ui.radio_button(
+                "Blinn-Phong",
+                &mut lighting.shading,
+                ShadingMode::BlinnPhong,
+            );
+
+            if lighting.shading == ShadingMode::Unlit {
+                return;
+            }
 
             ui.align_text_to_frame_padding();
-            ui.text("Speed:     ");
+            ui.text("Yaw:       ");
             ui.same_line();
-            ui.slider_config("##speed", 0.00001f32, 10.0f32)
-                .display_format("%.5f")
+            ui.slider_config("##light_yaw", -180.0f32, 180.0f32)
+                .display_format("%.1f")
+                .build(&mut lighting.light_yaw);
+
+            ui.align_text_to_frame_padding();
+            ui.text("Pitch:     ");
+            ui.same_line();
+            ui.slider_config("##light_pitch", -90.0f32, 90.0f32)
+                .display_format("%.1f")
                 .flags(imgui::SliderFlags::ALWAYS_CLAMP)
-                .build(&mut options.animation_speed);
+                .build(&mut lighting.light_pitch);
 
             ui.align_text_to_frame_padding();
-            ui.text("Time:      ");
+            ui.text("Color:     ");
             ui.same_line();
-            ui.slider_config(
-                "##time",
-                0.0f32,
-                mind_model.animations[mind_model.animation_selected].duration,
-            )
-            .display_format("%.5f")
-            .flags(imgui::SliderFlags::ALWAYS_CLAMP)
-            .build(&mut options.animation_time);
+            ui.color_edit3_config("##light_color", &mut lighting.light_color)
+                .inputs(false)
+                .build();
 
             ui.align_text_to_frame_padding();
-            ui.text("Animations:");
+            ui.text("Ambient:   ");
             ui.same_line();
-            ui.combo_simple_string(
-                "##animations",
-                &mut mind_model.animation_selected,
-                &mind_model.animations_file_names,
+            ui.slider_config("##light_ambient", 0.0f32, 1.0f32)
+                .display_format("%.2f")
+                .flags(imgui::SliderFlags::ALWAYS_CLAMP)
+                .build(&mut lighting.ambient);
+
+            if lighting.shading == ShadingMode::BlinnPhong {
+                ui.align_text_to_frame_padding();
+                ui.text("Specular:  ");
+                ui.same_line();
+                ui.slider_config("##light_specular", 0.0f32, 2.0f32)
+                    .display_format("%.2f")
+                    .flags(imgui::SliderFlags::ALWAYS_CLAMP)
+                    .build(&mut lighting.specular);
+
+                ui.align_text_to_frame_padding();
+                ui.text("Shininess: ");
+                ui.same_line();
+                ui.slider_config("##light_shininess", 1.0f32, 256.0f32)
+                    .display_format("%.1f")
+                    .flags(imgui::SliderFlags::LOGARITHMIC | imgui::SliderFlags::ALWAYS_CLAMP)
+                    .build(&mut lighting.shininess);
+            }
+        });
+}
+
+pub fn timeline(
+    ui: &imgui::Ui,
+    config_json: &mut ConfigJson,
+    mind_models: &[MindModel],
+    timeline_length: f32,
+    window_height: f32,
+) {
+    ui.window("Timeline")
+        .position([4.0f32, window_height - 4.0f32], imgui::Condition::Once)
+        .position_pivot([0.0f32, 1.0f32])
+        .always_auto_resize(true)
+        .build(|| {
+            let play_label = if config_json.timeline_playing {
+                "Pause"
+            } else {
+                "Play"
+            };
+            if ui.button(play_label) {
+                config_json.timeline_playing = !config_json.timeline_playing;
+            }
+            ui.same_line();
+            if ui.button("Rewind") {
+                config_json.timeline_time = 0.0f32;
+            }
+            ui.same_line();
+            ui.checkbox("Loop", &mut config_json.timeline_loop);
+
+            ui.align_text_to_frame_padding();
+            ui.text("Speed:");
+            ui.same_line();
+            ui.set_next_item_width(300.0f32);
+            ui.slider_config("##timeline_speed", 0.01f32, 10.0f32)
+                .display_format("%.3f")
+                .flags(imgui::SliderFlags::ALWAYS_CLAMP)
+                .build(&mut config_json.timeline_speed);
+
+            ui.align_text_to_frame_padding();
+            ui.text("Time: ");
+            ui.same_line();
+            ui.set_next_item_width(300.0f32);
+            ui.slider_config("##timeline_time", 0.0f32, timeline_length)
+                .display_format("%.5f")
+                .flags(imgui::SliderFlags::ALWAYS_CLAMP)
+                .build(&mut config_json.timeline_time);
+
+            ui.separator();
+
+            for i in 0..mind_models.len() {
+                let options = &mut config_json.options[i];
+                if !options.show || !options.use_animation {
+                    continue;
+                }
+
+                let _timeline_id = ui.push_id_usize(i);
+
+                ui.text(format!(
+                    "{} - {}",
+                    config_json.paths[i].name,
+                    mind_models[i].animations_file_names[mind_models[i].animation_selected]
+                ));
+
+                ui.set_next_item_width(100.0f32);
+                imgui::Drag::new("Offset##timeline_offset")
+                    .speed(0.01f32)
+                    .display_format("%.3f")
+                    .build(ui, &mut options.timeline_offset);
+                ui.same_line();
+                ui.set_next_item_width(100.0f32);
+                imgui::Drag::new("Speed##timeline_model_speed")
+                    .range(0.01f32, 10.0f32)
+                    .speed(0.01f32)
+                    .display_format("%.3f")
+                    .flags(imgui::SliderFlags::ALWAYS_CLAMP)
+                    .build(ui, &mut options.timeline_speed);
+                ui.same_line();
+                ui.radio_button("Hold", &mut options.timeline_end, TimelineEnd::Hold);
+                ui.same_line();
+                ui.radio_button("Loop", &mut options.timeline_end, TimelineEnd::Loop);
+                ui.same_line();
+                ui.radio_button("Stop", &mut options.timeline_end, TimelineEnd::Stop);
+            }
+        });
+}
+
+pub struct ModelUi {
+    pub joint_filter: String,
+    pub curve_view: CurveView,
+    pub animation_edit: AnimationEdit,
+    pub pose_gizmo: PoseGizmo,
+    pub hierarchy_filter: String,
+}
+
+impl ModelUi {
+    pub fn new() -> ModelUi {
+        ModelUi {
+            joint_filter: String::new(),
+            curve_view: CurveView::new(),
+            animation_edit: AnimationEdit::new(),
+            pose_gizmo: PoseGizmo::new(),
+            hierarchy_filter: String::new(),
+        }
+    }
+}
+
+pub fn dopesheet(
+    ui: &imgui::Ui,
+    config_json: &mut ConfigJson,
+    index: usize,
+    mind_model: &mut MindModel,
+    model_ui: &mut ModelUi,
+) {
+    let animation = &mind_model.animations[mind_model.animation_selected];
+    let current_time = crate::evaluated_time(&config_json.options[index], animation);
+
+    let mut show_dopesheet = config_json.options[index].show_dopesheet;
+    let mut new_time: Option<f32> = None;
+
+    ui.window(format!(
+        "Dopesheet - {}##dopesheet{}",
+        config_json.paths[index].name, index
+    ))
+    .size([700.0f32, 400.0f32], imgui::Condition::FirstUseEver)
+    .opened(&mut show_dopesheet)
+    .build(|| {
+        ui.text(format!(
+            "{} - {} tracks - {:.3} fps - {:.5}s",
+            mind_model.animations_file_names[mind_model.animation_selected],
+            animation.joints.len(),
+            animation.fps,
+            animation.duration
+        ));
+
+        ui.set_next_item_width(200.0f32);
+        ui.input_text("##joint_filter", &mut model_ui.joint_filter)
+            .hint("Filter joints")
+            .build();
+        ui.same_line();
+        ui.text_colored(DOPESHEET_COLORS[0], "Translation");
+        ui.same_line();
+        ui.text_colored(DOPESHEET_COLORS[1], "Rotation");
+        ui.same_line();
+        ui.text_colored(DOPESHEET_COLORS[2], "Scale");
+
+        let name_width = 200.0f32;
+        let track_width =
+            (ui.content_region_avail()[0] - name_width - ui.clone_style().item_spacing[0])
+                .max(100.0f32);
+        let time_to_x = |min_x: f32, time: f32| {
+            if animation.duration > 0.0f32 {
+                min_x + time / animation.duration * track_width
+            } else {
+                min_x
+            }
+        };
+
+        ui.align_text_to_frame_padding();
+        ui.text(format!("Time: {:.5}", current_time));
+        ui.same_line_with_pos(name_width + ui.clone_style().window_padding[0]);
+        if let Some(time) = dopesheet_scrub(ui, "##ruler", [track_width, 20.0f32], animation) {
+            new_time = Some(time);
+        }
+        {
+            let draw_list = ui.get_window_draw_list();
+            let (min, max) = (ui.item_rect_min(), ui.item_rect_max());
+            draw_list
+                .add_rect(min, max, [0.2f32, 0.2f32, 0.2f32, 1.0f32])
+                .filled(true)
+                .build();
+
+            let frame_count = (animation.duration * animation.fps).round() as usize;
+            let frame_step = ((frame_count as f32 * 5.0f32 / track_width).ceil() as usize).max(1);
+            for frame in (0..=frame_count).step_by(frame_step) {
+                let x = time_to_x(min[0], frame as f32 * animation.frame_delay);
+                let tick_height = if frame % (frame_step * 10) == 0 {
+                    12.0f32
+                } else {
+                    5.0f32
+                };
+                draw_list
+                    .add_line(
+                        [x, max[1] - tick_height],
+                        [x, max[1]],
+                        [0.6f32, 0.6f32, 0.6f32, 1.0f32],
+                    )
+                    .build();
+            }
+
+            let x = time_to_x(min[0], current_time);
+            draw_list
+                .add_triangle(
+                    [x - 5.0f32, min[1]],
+                    [x + 5.0f32, min[1]],
+                    [x, max[1]],
+                    DOPESHEET_PLAYHEAD,
+                )
+                .filled(true)
+                .build();
+        }
+
+        let joint_indices: HashMap<u32, usize> = mind_model
+            .skeleton
+            .joints
+            .iter()
+            .enumerate()
+            .map(|(joint_index, joint)| (joint.hash, joint_index))
+            .collect();
+        let joint_filter = model_ui.joint_filter.to_lowercase();
+        let tracks: Vec<(usize, Option<usize>, String)> = animation
+            .joints
+            .iter()
+            .enumerate()
+            .map(|(track_index, joint)| {
+                let joint_index = joint_indices.get(&joint.hash).copied();
+                let name = match joint_index {
+                    Some(joint_index) => mind_model.skeleton.joints[joint_index].name.to_owned(),
+                    None => format!("{:#010x}", joint.hash),
+                };
+                (track_index, joint_index, name)
+            })
+            .filter(|(_, _, name)| name.to_lowercase().contains(&joint_filter))
+            .collect();
+
+        let lane_height = 8.0f32;
+        let row_height = lane_height * 3.0f32;
+
+        ui.child_window("##tracks").build(|| {
+            let mut clipper = imgui::ListClipper::new(tracks.len() as i32)
+                .items_height(row_height + ui.clone_style().item_spacing[1])
+                .begin(ui);
+            while clipper.step() {
+                for row in clipper.display_start()..clipper.display_end() {
+                    let (track_index, joint_index, name) = &tracks[row as usize];
+                    let joint = &animation.joints[*track_index];
+                    let selected =
+                        joint_index.is_some() && mind_model.selected_joint == *joint_index;
+
+                    let _track_id = ui.push_id_usize(*track_index);
+
+                    if ui
+                        .selectable_config(name)
+                        .selected(selected)
+                        .size([name_width, row_height])
+                        .build()
+                        && joint_index.is_some()
+                    {
+                        mind_model.selected_joint = if selected { None } else { *joint_index };
+                    }
+                    if joint_index.is_none() && ui.is_item_hovered() {
+                        ui.tooltip(|| {
+                            ui.text("Track has no matching joint in skeleton");
+                        });
+                    }
+
+                    ui.same_line();
+                    if let Some(time) =
+                        dopesheet_scrub(ui, "##track", [track_width, row_height], animation)
+                    {
+                        new_time = Some(time);
+                    }
+
+                    let draw_list = ui.get_window_draw_list();
+                    let (min, max) = (ui.item_rect_min(), ui.item_rect_max());
+                    let background = if selected {
+                        [0.3f32, 0.3f32, 0.35f32, 1.0f32]
+                    } else {
+                        [0.15f32, 0.15f32, 0.15f32, 1.0f32]
+                    };
+                    draw_list
+                        .add_rect(min, max, background)
+                        .filled(true)
+                        .build();
+
+                    let lanes: [Vec<f32>; 3] = [
+                        joint.translations.iter().map(|x| x.0).collect(),
+                        joint.rotations.iter().map(|x| x.0).collect(),
+                        joint.scales.iter().map(|x| x.0).collect(),
+                    ];
+                    for (lane, times) in lanes.iter().enumerate() {
+                        let y = min[1] + lane_height * (lane as f32 + 0.5f32);
+                        for time in times.iter() {
+                            draw_list
+                                .add_circle(
+                                    [time_to_x(min[0], *time), y],
+                                    2.5f32,
+                                    DOPESHEET_COLORS[lane],
+                                )
+                                .filled(true)
+                                .build();
+                        }
+                    }
+
+                    let x = time_to_x(min[0], current_time);
+                    draw_list
+                        .add_line([x, min[1]], [x, max[1]], DOPESHEET_PLAYHEAD)
+                        .thickness(2.0f32)
+                        .build();
+                }
+            }
+        });
+    });
+
+    let options = &mut config_json.options[index];
+    options.show_dopesheet = show_dopesheet;
+
+    if let Some(time) = new_time {
+        if config_json.synchronized_time {
+            config_json.timeline_playing = false;
+            config_json.timeline_time = options.timeline_offset + time / options.timeline_speed;
+        } else {
+            options.play_animation = false;
+            options.animation_time = time;
+        }
+    }
+}
+
+const DOPESHEET_COLORS: [[f32; 4]; 3] = [
+    [1.0f32, 0.35f32, 0.35f32, 1.0f32],
+    [0.35f32, 1.0f32, 0.35f32, 1.0f32],
+    [0.4f32, 0.6f32, 1.0f32, 1.0f32],
+];
+const DOPESHEET_PLAYHEAD: [f32; 4] = [1.0f32, 0.8f32, 0.0f32, 1.0f32];
+
+fn dopesheet_scrub(ui: &imgui::Ui, id: &str, size: [f32; 2], animation: &Animation) -> Option<f32> {
+    ui.invisible_button(id, size);
+    if ui.is_item_active() {
+        let min_x = ui.item_rect_min()[0];
+        let lerp_value = ((ui.io().mouse_pos[0] - min_x) / size[0]).clamp(0.0f32, 1.0f32);
+        Some(lerp_value * animation.duration)
+    } else {
+        None
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum CurveChannel {
+    Translation,
+    Rotation,
+    Scale,
+}
+
+pub struct CurveView {
+    pub channel: CurveChannel,
+    pub euler: bool,
+    pub zoom: f32,
+    pub start: f32,
+}
+
+impl CurveView {
+    pub fn new() -> CurveView {
+        CurveView {
+            channel: CurveChannel::Rotation,
+            euler: false,
+            zoom: 1.0f32,
+            start: 0.0f32,
+        }
+    }
+}
+
+pub fn curves(
+    ui: &imgui::Ui,
+    config_json: &mut ConfigJson,
+    index: usize,
+    mind_model: &mut MindModel,
+    model_ui: &mut ModelUi,
+) {
+    let animation = &mind_model.animations[mind_model.animation_selected];
+    let current_time = crate::evaluated_time(&config_json.options[index], animation);
+
+    let mut show_curves = config_json.options[index].show_curves;
+
+    ui.window(format!(
+        "Curves - {}##curves{}",
+        config_json.paths[index].name, index
+    ))
+    .size([700.0f32, 400.0f32], imgui::Condition::FirstUseEver)
+    .opened(&mut show_curves)
+    .build(|| {
+        let mut joint_selected = mind_model.selected_joint.map_or(0, |joint| joint + 1);
+        let joint_names: Vec<&str> = std::iter::once("None")
+            .chain(
+                mind_model
+                    .skeleton
+                    .joints
+                    .iter()
+                    .map(|joint| joint.name.as_str()),
+            )
+            .collect();
+        ui.align_text_to_frame_padding();
+        ui.text("Joint:     ");
+        ui.same_line();
+        ui.set_next_item_width(200.0f32);
+        if ui.combo_simple_string("##curve_joint", &mut joint_selected, &joint_names) {
+            mind_model.selected_joint = joint_selected.checked_sub(1);
+        }
+
+        let curve_view = &mut model_ui.curve_view;
+        ui.same_line();
+        ui.radio_button(
+            "Translation",
+            &mut curve_view.channel,
+            CurveChannel::Translation,
+        );
+        ui.same_line();
+        ui.radio_button("Rotation", &mut curve_view.channel, CurveChannel::Rotation);
+        ui.same_line();
+        ui.radio_button("Scale", &mut curve_view.channel, CurveChannel::Scale);
+        if curve_view.channel == CurveChannel::Rotation {
+            ui.same_line();
+            ui.checkbox("Euler Angles", &mut curve_view.euler);
+        }
+
+        ui.align_text_to_frame_padding();
+        ui.text("Zoom:      ");
+        ui.same_line();
+        ui.set_next_item_width(200.0f32);
+        ui.slider_config("##curve_zoom", 1.0f32, 100.0f32)
+            .display_format("%.2fx")
+            .flags(imgui::SliderFlags::ALWAYS_CLAMP | imgui::SliderFlags::LOGARITHMIC)
+            .build(&mut curve_view.zoom);
+        ui.same_line();
+        if ui.button("Reset Zoom") {
+            curve_view.zoom = 1.0f32;
+            curve_view.start = 0.0f32;
+        }
+
+        let joint = mind_model.selected_joint.and_then(|joint_index| {
+            let hash = mind_model.skeleton.joints[joint_index].hash;
+            animation.joints.iter().find(|joint| joint.hash == hash)
+        });
+        let joint = match joint {
+            Some(joint) => joint,
+            None => {
+                ui.text("Select a joint with an animation track");
+                return;
+            }
+        };
+
+        let (component_names, keys): (&[&str], Vec<(f32, Vec<f32>)>) = match curve_view.channel {
+            CurveChannel::Translation => (
+                &["X", "Y", "Z"],
+                joint
+                    .translations
+                    .iter()
+                    .map(|(time, value)| (*time, value.to_array().to_vec()))
+                    .collect(),
+            ),
+            CurveChannel::Rotation if curve_view.euler => (
+                &["X", "Y", "Z"],
+                joint
+                    .rotations
+                    .iter()
+                    .map(|(time, value)| (*time, curve_euler(value)))
+                    .collect(),
+            ),
+            CurveChannel::Rotation => (
+                &["X", "Y", "Z", "W"],
+                joint
+                    .rotations
+                    .iter()
+                    .map(|(time, value)| (*time, value.to_array().to_vec()))
+                    .collect(),
+            ),
+            CurveChannel::Scale => (
+                &["X", "Y", "Z"],
+                joint
+                    .scales
+                    .iter()
+                    .map(|(time, value)| (*time, value.to_array().to_vec()))
+                    .collect(),
+            ),
+        };
+        let sample = |time: f32| -> Vec<f32> {
+            let pose = sample_joint(joint, time);
+            match curve_view.channel {
+                CurveChannel::Translation => pose.translation.to_array().to_vec(),
+                CurveChannel::Rotation if curve_view.euler => curve_euler(&pose.rotation),
+                CurveChannel::Rotation => pose.rotation.to_array().to_vec(),
+                CurveChannel::Scale => pose.scale.to_array().to_vec(),
+            }
+        };
+
+        let plot_size = [
+            ui.content_region_avail()[0],
+            ui.content_region_avail()[1].max(100.0f32),
+        ];
+        ui.invisible_button("##curve_plot", plot_size);
+        let (min, max) = (ui.item_rect_min(), ui.item_rect_max());
+
+        let view_duration = animation.duration / curve_view.zoom;
+        if ui.is_item_hovered() && ui.io().mouse_wheel != 0.0f32 && animation.duration > 0.0f32 {
+            let mouse_time =
+                curve_view.start + (ui.io().mouse_pos[0] - min[0]) / plot_size[0] * view_duration;
+            curve_view.zoom =
+                (curve_view.zoom * 1.2f32.powf(ui.io().mouse_wheel)).clamp(1.0f32, 100.0f32);
+            curve_view.start = mouse_time
+                - (ui.io().mouse_pos[0] - min[0]) / plot_size[0] * animation.duration
+                    / curve_view.zoom;
+        }
+        if ui.is_item_active() {
+            curve_view.start -= ui.io().mouse_delta[0] / plot_size[0] * view_duration;
+        }
+        let view_duration = animation.duration / curve_view.zoom;
+        curve_view.start = curve_view
+            .start
+            .clamp(0.0f32, animation.duration - view_duration);
+
+        let samples: Vec<(f32, Vec<f32>)> = (0..=plot_size[0] as usize)
+            .map(|x| {
+                let time = curve_view.start + x as f32 / plot_size[0] * view_duration;
+                (time, sample(time))
+            })
+            .collect();
+
+        let (mut value_min, mut value_max) = (f32::MAX, f32::MIN);
+        for (_, values) in samples.iter().chain(keys.iter()) {
+            for value in values.iter() {
+                value_min = value_min.min(*value);
+                value_max = value_max.max(*value);
+            }
+        }
+        let padding = ((value_max - value_min) * 0.05f32).max(0.001f32);
+        value_min -= padding;
+        value_max += padding;
+
+        let to_screen = |time: f32, value: f32| {
+            [
+                min[0] + (time - curve_view.start) / view_duration.max(f32::EPSILON) * plot_size[0],
+                max[1] - (value - value_min) / (value_max - value_min) * plot_size[1],
+            ]
+        };
+
+        let draw_list = ui.get_window_draw_list();
+        draw_list
+            .add_rect(min, max, [0.12f32, 0.12f32, 0.12f32, 1.0f32])
+            .filled(true)
+            .build();
+        draw_list.with_clip_rect_intersect(min, max, || {
+            if value_min < 0.0f32 && value_max > 0.0f32 {
+                draw_list
+                    .add_line(
+                        to_screen(curve_view.start, 0.0f32),
+                        to_screen(curve_view.start + view_duration, 0.0f32),
+                        [0.4f32, 0.4f32, 0.4f32, 1.0f32],
+                    )
+                    .build();
+            }
+
+            for component in 0..component_names.len() {
+                let points: Vec<[f32; 2]> = samples
+                    .iter()
+                    .map(|(time, values)| to_screen(*time, values[component]))
+                    .collect();
+                draw_list
+                    .add_polyline(points, CURVE_COLORS[component])
+                    .thickness(1.5f32)
+                    .build();
+
+                for (time, values) in keys.iter() {
+                    draw_list
+                        .add_circle(
+                            to_screen(*time, values[component]),
+                            3.0f32,
+                            CURVE_COLORS[component],
+                        )
+                        .filled(true)
+                        .build();
+                }
+            }
+
+            let playhead = to_screen(current_time, value_min);
+            draw_list
+                .add_line(
+                    [playhead[0], min[1]],
+                    [playhead[0], max[1]],
+                    DOPESHEET_PLAYHEAD,
+                )
+                .build();
+        });
+
+        draw_list.add_text(
+            [min[0] + 4.0f32, min[1] + 2.0f32],
+            [0.8f32, 0.8f32, 0.8f32, 1.0f32],
+            format!("{:.4}", value_max),
+        );
+        draw_list.add_text(
+            [min[0] + 4.0f32, max[1] - ui.text_line_height() - 2.0f32],
+            [0.8f32, 0.8f32, 0.8f32, 1.0f32],
+            format!("{:.4}", value_min),
+        );
+
+        if ui.is_item_hovered() {
+            let mouse_pos = ui.io().mouse_pos;
+            let time = (curve_view.start + (mouse_pos[0] - min[0]) / plot_size[0] * view_duration)
+                .clamp(0.0f32, animation.duration);
+            draw_list
+                .add_line(
+                    [mouse_pos[0], min[1]],
+                    [mouse_pos[0], max[1]],
+                    [0.7f32, 0.7f32, 0.7f32, 0.5f32],
+                )
+                .build();
+
+            let nearest_key = keys
+                .iter()
+                .flat_map(|(key_time, values)| {
+                    values
+                        .iter()
+                        .enumerate()
+                        .map(move |(component, value)| (*key_time, component, *value))
+                })
+                .map(|(key_time, component, value)| {
+                    let position = to_screen(key_time, value);
+                    let distance =
+                        (position[0] - mouse_pos[0]).powi(2) + (position[1] - mouse_pos[1]).powi(2);
+                    (distance, key_time, component, value)
+                })
+                .filter(|(distance, ..)| *distance < 36.0f32)
+                .min_by(|a, b| a.0.total_cmp(&b.0));
+
+            let values = sample(time);
+            ui.tooltip(|| {
+                ui.text(format!(
+                    "Time: {:.5} - Frame: {:.2}",
+                    time,
+                    time / animation.frame_delay
+                ));
+                for (component, value) in values.iter().enumerate() {
+                    ui.text_colored(
+                        CURVE_COLORS[component],
+                        format!("{}: {:.5}", component_names[component], value),
+                    );
+                }
+                if let Some((_, key_time, component, value)) = nearest_key {
+                    ui.separator();
+                    ui.text(format!(
+                        "Key {} at {:.5}: {:.5}",
+                        component_names[component], key_time, value
+                    ));
+                }
+            });
+        }
+    });
+
+    config_json.options[index].show_curves = show_curves;
+}
+
+const CURVE_COLORS: [[f32; 4]; 4] = [
+    [1.0f32, 0.35f32, 0.35f32, 1.0f32],
+    [0.35f32, 1.0f32, 0.35f32, 1.0f32],
+    [0.4f32, 0.6f32, 1.0f32, 1.0f32],
+    [1.0f32, 0.9f32, 0.3f32, 1.0f32],
+];
+
+fn curve_euler(rotation: &glam::Quat) -> Vec<f32> {
+    let (x, y, z) = rotation.to_euler(glam::EulerRot::XYZ);
+    vec![x.to_degrees(), y.to_degrees(), z.to_degrees()]
+}
+
+pub fn diagnostics(
+    ui: &imgui::Ui,
+    config_json: &mut ConfigJson,
+    index: usize,
+    mind_models: &[MindModel],
+) {
+    let mind_model = &mind_models[index];
+    let report = match &mind_model.diagnostics {
+        Some(report) => report,
+        None => return,
+    };
+
+    let mut show_diagnostics = config_json.options[index].show_diagnostics;
+
+    ui.window(format!(
+        "Diagnostics - {}##diagnostics{}",
+        config_json.paths[index].name, index
+    ))
+    .size([500.0f32, 400.0f32], imgui::Condition::FirstUseEver)
+    .opened(&mut show_diagnostics)
+    .build(|| {
+        ui.text(format!("Animation: {}", report.animation_name));
+        ui.text(format!(
+            "{:.3} fps - {:.5}s - {} frames - {} tracks - {} skeleton joints",
+            report.fps,
+            report.duration,
+            report.frame_count,
+            report.tracks.len(),
+            mind_model.skeleton.joints.len()
+        ));
+
+        let export_path = format!("export/{}", config_json.paths[index].name);
+        if ui.button("Save Report") {
+            diagnostics::write_reports(
+                Path::new(&format!(
+                    "{export_path}/diagnostics_{}.json",
+                    report.animation_name
+                )),
+                std::slice::from_ref(report),
             );
+        }
+        ui.same_line();
+        if ui.button("Save All Animations") {
+            let reports: Vec<AnimationReport> = (0..mind_model.animations.len())
+                .map(|animation_index| crate::animation_report(mind_models, index, animation_index))
+                .collect();
+            diagnostics::write_reports(
+                Path::new(&format!("{export_path}/diagnostics.json")),
+                &reports,
+            );
+        }
+
+        let warning_color = [1.0f32, 0.7f32, 0.2f32, 1.0f32];
+
+        ui.tree_node_config(&format!(
+            "Joints Without Track ({})###missing_tracks",
+            report.missing_tracks.len()
+        ))
+        .flags(imgui::TreeNodeFlags::SPAN_AVAIL_WIDTH)
+        .framed(true)
+        .build(|| {
+            for name in report.missing_tracks.iter() {
+                ui.text_colored(warning_color, name);
+            }
         });
 
-    ui.tree_node_config("Meshes")
+        ui.tree_node_config(&format!(
+            "Tracks Without Joint ({})###unmatched_tracks",
+            report.unmatched_tracks.len()
+        ))
         .flags(imgui::TreeNodeFlags::SPAN_AVAIL_WIDTH)
         .framed(true)
         .build(|| {
-            for i in 0..mind_model.skin.meshes.len() {
-                let _meshes_id = ui.push_id_usize(i);
-                ui.checkbox(
-                    mind_model.skin.meshes[i].submesh.name.as_str(),
-                    &mut mind_model.show_meshes[i],
-                );
-                if mind_model.show_meshes[i] {
-                    ui.combo_simple_string(
-                        "##texture",
-                        &mut mind_model.textures_selecteds[i],
-                        &mind_model.textures_file_names,
-                    );
-                    imgui::Image::new(
-                        imgui::TextureId::new(
-                            mind_model.textures[mind_model.textures_selecteds[i]].id as usize,
-                        ),
-                        [64.0f32, 64.0f32],
-                    )
-                    .build(ui);
-                }
+            for track in report.unmatched_tracks.iter() {
+                ui.text_colored(warning_color, track.display_name());
             }
         });
 
-    ui.tree_node_config("Export")
+        ui.tree_node_config(&format!(
+            "Constant Tracks ({})###constant_tracks",
+            report.constant_tracks.len()
+        ))
         .flags(imgui::TreeNodeFlags::SPAN_AVAIL_WIDTH)
         .framed(true)
         .build(|| {
-            ui.radio_button("Export as gltf", export_as, 0);
-            ui.radio_button("Export as glb", export_as, 1);
-            if ui.button_with_size("Export Model", [ui.content_region_avail()[0], 0.0f32]) {
-                export::export_model(*export_as, name, mind_model);
+            for track in report.constant_tracks.iter() {
+                ui.text(track.display_name());
+            }
+        });
+
+        ui.tree_node_config("Keyframe Counts")
+            .flags(imgui::TreeNodeFlags::SPAN_AVAIL_WIDTH)
+            .framed(true)
+            .build(|| {
+                ui.columns(4, "##keyframe_counts", true);
+                for header in ["Track", "Translation", "Rotation", "Scale"] {
+                    ui.text(header);
+                    ui.next_column();
+                }
+                ui.separator();
+
+                for track in report.tracks.iter() {
+                    if track.matches_skeleton {
+                        ui.text(track.display_name());
+                    } else {
+                        ui.text_colored(warning_color, track.display_name());
+                    }
+                    ui.next_column();
+                    ui.text(track.translation_keys.to_string());
+                    ui.next_column();
+                    ui.text(track.rotation_keys.to_string());
+                    ui.next_column();
+                    ui.text(track.scale_keys.to_string());
+                    ui.next_column();
+                }
+                ui.columns(1, "##keyframe_counts", false);
+            });
+    });
+
+    config_json.options[index].show_diagnostics = show_diagnostics;
+}
+
+pub fn validation(
+    ui: &imgui::Ui,
+    config_json: &mut ConfigJson,
+    index: usize,
+    mind_model: &mut MindModel,
+) {
+    let report = match &mind_model.validation {
+        Some(report) => report,
+        None => return,
+    };
+
+    let mut show_validation = config_json.options[index].show_validation;
+    let mut revalidate = false;
+
+    ui.window(format!(
+        "Validation - {}##validation{}",
+        config_json.paths[index].name, index
+    ))
+    .size([500.0f32, 400.0f32], imgui::Condition::FirstUseEver)
+    .opened(&mut show_validation)
+    .build(|| {
+        ui.text(format!(
+            "{} vertices - {} indices - {} submeshes - {} skeleton joints",
+            report.vertex_count,
+            report.index_count,
+            report.submeshes.len(),
+            report.joint_count
+        ));
+
+        let warning_color = [1.0f32, 0.7f32, 0.2f32, 1.0f32];
+        let issue_count = report.issue_count();
+        if issue_count == 0 {
+            ui.text_colored([0.3f32, 1.0f32, 0.3f32, 1.0f32], "No issues found");
+        } else {
+            ui.text_colored(warning_color, format!("{issue_count} issues found"));
+        }
+
+        if ui.button("Save Report") {
+            validation::write_report(
+                Path::new(&format!(
+                    "export/{}/validation.json",
+                    config_json.paths[index].name
+                )),
+                report,
+            );
+        }
+        ui.same_line();
+        revalidate = ui.button("Revalidate");
+
+        validation_list(ui, "Empty Submeshes", &report.empty_submeshes);
+        validation_list(ui, "Unused Vertices", &report.unused_vertices);
+        validation_list(
+            ui,
+            "Weights Not Summing To One",
+            &report.unnormalized_weights,
+        );
+        validation_list(
+            ui,
+            "Influences Outside Skeleton",
+            &report.influences_outside_skeleton,
+        );
+        validation_list(
+            ui,
+            "Influences Over 256 Matrices",
+            &report.influences_over_matrix_limit,
+        );
+        validation_list(ui, "NaN Normals", &report.nan_normals);
+
+        ui.tree_node_config("Submeshes")
+            .flags(imgui::TreeNodeFlags::SPAN_AVAIL_WIDTH)
+            .framed(true)
+            .build(|| {
+                for (i, submesh) in report.submeshes.iter().enumerate() {
+                    let _submesh_id = ui.push_id_usize(i);
+                    if submesh.range_out_of_bounds {
+                        ui.text_colored(
+                            warning_color,
+                            format!(
+                                "{}: range {}..{} exceeds index buffer",
+                                submesh.name,
+                                submesh.indices_offset,
+                                submesh.indices_offset + submesh.indices_count
+                            ),
+                        );
+                    } else if submesh.misaligned_offset {
+                        ui.text_colored(
+                            warning_color,
+                            format!(
+                                "{}: offset {} is not a multiple of 3",
+                                submesh.name, submesh.indices_offset
+                            ),
+                        );
+                    } else {
+                        ui.text(format!(
+                            "{}: {} triangles",
+                            submesh.name,
+                            submesh.indices_count / 3
+                        ));
+                    }
+                    ui.indent();
+                    validation_list(ui, "Out Of Range Indices", &submesh.out_of_range_indices);
+                    validation_list(ui, "Degenerate Triangles", &submesh.degenerate_triangles);
+                    validation_list(ui, "Duplicate Triangles", &submesh.duplicate_triangles);
+                    ui.unindent();
+                }
+            });
+    });
+
+    if revalidate {
+        mind_model.validation = None;
+    }
+    config_json.options[index].show_validation = show_validation;
+}
+
+fn validation_list<T: std::fmt::Display>(ui: &imgui::Ui, label: &str, items: &[T]) {
+    let flags = if items.is_empty() {
+        imgui::TreeNodeFlags::SPAN_AVAIL_WIDTH | imgui::TreeNodeFlags::LEAF
+    } else {
+        imgui::TreeNodeFlags::SPAN_AVAIL_WIDTH
+    };
+
+    ui.tree_node_config(&format!("{label} ({})###{label}", items.len()))
+        .flags(flags)
+        .build(|| {
+            ui.text_wrapped(
+                items
+                    .iter()
+                    .map(|item| item.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", "),
+            );
+        });
+}
+
+pub fn hierarchy(
+    ui: &imgui::Ui,
+    config_json: &mut ConfigJson,
+    index: usize,
+    mind_model: &mut MindModel,
+    model_ui: &mut ModelUi,
+) {
+    let options = &config_json.options[index];
+    let animated = options.use_animation || mind_model.posing.enabled;
+    let mut show_hierarchy = options.show_hierarchy;
+
+    ui.window(format!(
+        "Skeleton - {}##hierarchy{}",
+        config_json.paths[index].name, index
+    ))
+    .size([420.0f32, 600.0f32], imgui::Condition::FirstUseEver)
+    .opened(&mut show_hierarchy)
+    .build(|| {
+        ui.text(format!(
+            "{} joints - {} influences",
+            mind_model.skeleton.joints.len(),
+            mind_model.skeleton.influences.len()
+        ));
+
+        ui.set_next_item_width(ui.content_region_avail()[0]);
+        ui.input_text("##hierarchy_filter", &mut model_ui.hierarchy_filter)
+            .hint("Search name or hash")
+            .build();
+
+        ui.child_window("##hierarchy_tree")
+            .size([0.0f32, -260.0f32])
+            .border(true)
+            .build(|| {
+                let filter = model_ui.hierarchy_filter.to_lowercase();
+                if filter.is_empty() {
+                    for i in 0..mind_model.skeleton.joints.len() {
+                        if mind_model.skeleton.joints[i].parent_id == -1 {
+                            hierarchy_node(ui, mind_model, i);
+                        }
+                    }
+                } else {
+                    for (i, joint) in mind_model.skeleton.joints.iter().enumerate() {
+                        let matches = joint.name.to_lowercase().contains(&filter)
+                            || format!("{:#010x}", joint.hash).contains(&filter);
+                        if matches
+                            && ui
+                                .selectable_config(format!("{}##joint{}", joint.name, i))
+                                .selected(mind_model.selected_joint == Some(i))
+                                .build()
+                        {
+                            mind_model.selected_joint = Some(i);
+                        }
+                    }
+                }
+            });
+
+        let Some(selected_joint) = mind_model.selected_joint else {
+            ui.text("Select a joint to inspect it");
+            return;
+        };
+
+        ui.child_window("##joint_inspector").build(|| {
+            joint_inspector(ui, mind_model, selected_joint, animated);
+        });
+    });
+
+    config_json.options[index].show_hierarchy = show_hierarchy;
+}
+
+pub fn selection(
+    ui: &imgui::Ui,
+    config_json: &ConfigJson,
+    index: usize,
+    mind_model: &mut MindModel,
+) {
+    let mut show_selection = mind_model.show_selection;
+
+    ui.window(format!(
+        "Selection - {}##selection{}",
+        config_json.paths[index].name, index
+    ))
+    .size([320.0f32, 0.0f32], imgui::Condition::FirstUseEver)
+    .always_auto_resize(true)
+    .opened(&mut show_selection)
+    .build(|| {
+        if let Some(selected_joint) = mind_model.selected_joint {
+            let joint = &mind_model.skeleton.joints[selected_joint];
+            ui.text(format!("Joint: {} ({})", joint.name, joint.id));
+            ui.text(format!("Hash: {:#010x}", joint.hash));
+            ui.text(format!(
+                "Parent: {}",
+                if joint.parent_id != -1 {
+                    mind_model.skeleton.joints[joint.parent_id as usize]
+                        .name
+                        .as_str()
+                } else {
+                    "None"
+                }
+            ));
+            if ui.button("Clear Joint") {
+                mind_model.selected_joint = None;
+            }
+        }
+
+        if let Some(selected_mesh) = mind_model.selected_mesh {
+            if mind_model.selected_joint.is_some() {
+                ui.separator();
+            }
+
+            let mesh = &mind_model.skin.meshes[selected_mesh];
+            let start = mesh.submesh.indices_offset as usize;
+            let end = start + mesh.submesh.indices_count as usize;
+            let mut vertices: Vec<u16> = mind_model.skin.indices[start..end].to_vec();
+            vertices.sort_unstable();
+            vertices.dedup();
+
+            ui.text(format!("Submesh: {}", mesh.submesh.name));
+            ui.text(format!("Hash: {:#010x}", mesh.hash));
+            ui.text(format!(
+                "{} triangles - {} vertices",
+                mesh.submesh.indices_count / 3,
+                vertices.len()
+            ));
+            ui.text(format!(
+                "Texture: {}",
+                mind_model.textures_file_names[mind_model.textures_selecteds[selected_mesh]]
+            ));
+            ui.checkbox("Visible", &mut mind_model.show_meshes[selected_mesh]);
+            ui.same_line();
+            if ui.button("Clear Submesh") {
+                mind_model.selected_mesh = None;
+            }
+        }
+
+        if mind_model.selected_joint.is_none() && mind_model.selected_mesh.is_none() {
+            ui.text("Click a joint or submesh to select it");
+        }
+    });
+
+    mind_model.show_selection = show_selection;
+}
+
+fn hierarchy_node(ui: &imgui::Ui, mind_model: &mut MindModel, joint_index: usize) {
+    let joint = &mind_model.skeleton.joints[joint_index];
+    let mut flags = imgui::TreeNodeFlags::OPEN_ON_ARROW
+        | imgui::TreeNodeFlags::OPEN_ON_DOUBLE_CLICK
+        | imgui::TreeNodeFlags::SPAN_AVAIL_WIDTH;
+    if joint.children.is_empty() {
+        flags |= imgui::TreeNodeFlags::LEAF;
+    }
+    if mind_model.selected_joint == Some(joint_index) {
+        flags |= imgui::TreeNodeFlags::SELECTED;
+    }
+
+    let node = ui
+        .tree_node_config(format!("{}##joint{}", joint.name, joint_index))
+        .flags(flags)
+        .push();
+    if ui.is_item_clicked() && !ui.is_item_toggled_open() {
+        mind_model.selected_joint = Some(joint_index);
+    }
+
+    if let Some(_node) = node {
+        let children = mind_model.skeleton.joints[joint_index].children.to_owned();
+        for child in children {
+            hierarchy_node(ui, mind_model, child);
+        }
+    }
+}
+
+fn joint_inspector(ui: &imgui::Ui, mind_model: &MindModel, joint_index: usize, animated: bool) {
+    let skeleton = &mind_model.skeleton;
+    let joint = &skeleton.joints[joint_index];
+
+    ui.text(format!("Id: {}   Name: {}", joint.id, joint.name));
+    ui.text(format!(
+        "Parent: {}   Children: {}",
+        if joint.parent_id != -1 {
+            skeleton.joints[joint.parent_id as usize].name.as_str()
+        } else {
+            "None"
+        },
+        joint.children.len()
+    ));
+
+    let elf_hash = hasher::string_to_hash(&joint.name);
+    let fnv1a_hash = hasher::fnv1a(&joint.name);
+    let hash_source = |hash: u32| {
+        if hash == joint.hash {
+            " (skeleton)"
+        } else {
+            ""
+        }
+    };
+    ui.text(format!(
+        "ELF Hash:    {:#010x}{}",
+        elf_hash,
+        hash_source(elf_hash)
+    ));
+    ui.text(format!(
+        "FNV-1a Hash: {:#010x}{}",
+        fnv1a_hash,
+        hash_source(fnv1a_hash)
+    ));
+
+    let influence_count = mind_model
+        .skin
+        .influences
+        .iter()
+        .zip(mind_model.skin.weights.iter())
+        .filter(|(influence, weight)| {
+            (0..4).any(|i| influence[i] as usize == joint_index && weight[i] > 0.0f32)
+        })
+        .count();
+    let is_influence = skeleton.influences.contains(&(joint_index as u16));
+    ui.text(format!(
+        "Influences {} vertices{}",
+        influence_count,
+        if is_influence {
+            ""
+        } else {
+            " (not in influence list)"
+        }
+    ));
+
+    ui.separator();
+
+    joint_trs(ui, "Bind Local", &joint.local_matrix);
+    joint_trs(ui, "Bind Global", &joint.global_matrix);
+
+    if animated {
+        let global_matrix = mind_model.joints_transforms[joint_index] * joint.global_matrix;
+        let local_matrix = if joint.parent_id != -1 {
+            let parent = joint.parent_id as usize;
+            (mind_model.joints_transforms[parent] * skeleton.joints[parent].global_matrix).inverse()
+                * global_matrix
+        } else {
+            global_matrix
+        };
+        joint_trs(ui, "Animated Local", &local_matrix);
+        joint_trs(ui, "Animated Global", &global_matrix);
+    } else {
+        ui.text_disabled("Enable animation or posing to see the animated pose");
+    }
+}
+
+fn joint_trs(ui: &imgui::Ui, label: &str, matrix: &glam::Mat4) {
+    let (scale, rotation, translation) = matrix.to_scale_rotation_translation();
+    let euler = curve_euler(&rotation);
+    ui.text(label);
+    ui.text(format!(
+        "  T: {:>10.3} {:>10.3} {:>10.3}",
+        translation.x, translation.y, translation.z
+    ));
+    ui.text(format!(
+        "  R: {:>10.3} {:>10.3} {:>10.3}",
+        euler[0], euler[1], euler[2]
+    ));
+    ui.text(format!(
+        "  S: {:>10.3} {:>10.3} {:>10.3}",
+        scale.x, scale.y, scale.z
+    ));
+}
+
+pub fn model(
+    ui: &imgui::Ui,
+    options: &mut OptionsJson,
+    mind_model: &mut MindModel,
+    model_ui: &mut ModelUi,
+    export_as: &mut u8,
+    name: &String,
+    animations_path: &str,
+) {
+    let render_mode_names: Vec<&str> = RENDER_MODES.iter().map(|(name, _)| *name).collect();
+    let mut render_mode = options.render_mode as usize;
+    ui.align_text_to_frame_padding();
+    ui.text("Render:    ");
+    ui.same_line();
+    if ui.combo_simple_string("##render_mode", &mut render_mode, &render_mode_names) {
+        options.render_mode = RENDER_MODES[render_mode].1;
+    }
+    if options.render_mode == RenderMode::JointIndex {
+        if let Some(joint) = mind_model.selected_joint {
+            let [r, g, b] = model::id_color(joint as u32);
+            ui.color_button("##selected_joint_color", [r, g, b, 1.0f32]);
+            ui.same_line();
+            ui.text(&mind_model.skeleton.joints[joint].name);
+        }
+    }
+
+    ui.checkbox("Show Wireframe", &mut options.show_wireframe);
+    wireframe_overlay(ui, options);
+    vertex_vectors(ui, options, mind_model);
+    ui.checkbox("Show Skeleton Names", &mut options.show_skeleton_names);
+    ui.checkbox("Show Skeleton Bones", &mut options.show_skeleton_bones);
+    ui.checkbox("Show Skeleton Joints", &mut options.show_skeleton_joints);
+    ui.checkbox("Show Joint Axes", &mut options.joint_axes.show);
+    if options.joint_axes.show {
+        joint_axes(ui, options, mind_model);
+    }
+    ui.checkbox("Show Diagnostics", &mut options.show_diagnostics);
+    ui.checkbox("Show Hierarchy", &mut options.show_hierarchy);
+    ui.checkbox("Show Validation", &mut options.show_validation);
+
+    weight_heatmap(ui, options, mind_model);
+
+    ui.tree_node_config("Animations")
+        .flags(imgui::TreeNodeFlags::SPAN_AVAIL_WIDTH)
+        .framed(true)
+        .build(|| {
+            ui.checkbox("Use Animation", &mut options.use_animation);
+            ui.checkbox("Play / Stop", &mut options.play_animation);
+            ui.checkbox("Loop Animation", &mut options.loop_animation);
+            ui.checkbox("Next Animation", &mut options.next_animation);
+            ui.checkbox("Snap To Frames", &mut options.snap_to_frames);
+            ui.checkbox("Show Dopesheet", &mut options.show_dopesheet);
+            ui.checkbox("Show Curves", &mut options.show_curves);
+
+            ui.radio_button("Forward", &mut options.playback_mode, PlaybackMode::Forward);
+            ui.same_line();
+            ui.radio_button("Reverse", &mut options.playback_mode, PlaybackMode::Reverse);
+            ui.same_line();
+            if ui.radio_button(
+                "Ping-Pong",
+                &mut options.playback_mode,
+                PlaybackMode::PingPong,
+            ) {
+                options.ping_pong_reverse = false;
+            }
+
+            ui.text("CTRL+Click Change To Input");
+
+            ui.align_text_to_frame_padding();
+            ui.text("Speed:     ");
+            ui.same_line();
+            ui.slider_config("##speed", 0.00001f32, 10.0f32)
+                .display_format("%.5f")
+                .flags(imgui::SliderFlags::ALWAYS_CLAMP)
+                .build(&mut options.animation_speed);
+
+            ui.align_text_to_frame_padding();
+            ui.text("Time:      ");
+            ui.same_line();
+            ui.slider_config(
+                "##time",
+                0.0f32,
+                mind_model.animations[mind_model.animation_selected].duration,
+            )
+            .display_format("%.5f")
+            .flags(imgui::SliderFlags::ALWAYS_CLAMP)
+            .build(&mut options.animation_time);
+
+            let animation = &mind_model.animations[mind_model.animation_selected];
+
+            ui.align_text_to_frame_padding();
+            ui.text("Frame:     ");
+            ui.same_line();
+            if ui.arrow_button("##step_back", imgui::Direction::Left) {
+                crate::step_animation(options, mind_model, -1);
+            }
+            ui.same_line();
+            if ui.arrow_button("##step_forward", imgui::Direction::Right) {
+                crate::step_animation(options, mind_model, 1);
+            }
+            ui.same_line();
+            ui.text(format!(
+                "{} / {}",
+                (options.animation_time / animation.frame_delay).round(),
+                (animation.duration / animation.frame_delay).round()
+            ));
+            if ui.is_item_hovered() {
+                ui.tooltip(|| {
+                    ui.text("Left / Right arrow keys step one frame");
+                });
+            }
+
+            if ui.checkbox("Use Range", &mut options.use_animation_range)
+                && options.animation_range[1] <= options.animation_range[0]
+            {
+                options.animation_range = [0.0f32, animation.duration];
+            }
+            if options.use_animation_range {
+                ui.align_text_to_frame_padding();
+                ui.text("Range:     ");
+                ui.same_line();
+                ui.slider_config("##range", 0.0f32, animation.duration)
+                    .display_format("%.5f")
+                    .flags(imgui::SliderFlags::ALWAYS_CLAMP)
+                    .build_array(&mut options.animation_range);
+                if options.animation_range[1] < options.animation_range[0] {
+                    options.animation_range[1] = options.animation_range[0];
+                }
+            }
+
+            ui.align_text_to_frame_padding();
+            ui.text("Animations:");
+            ui.same_line();
+            ui.combo_simple_string(
+                "##animations",
+                &mut mind_model.animation_selected,
+                &mind_model.animations_file_names,
+            );
+
+            animation_layers(ui, mind_model);
+
+            ui.tree_node_config("Root Motion")
+                .flags(imgui::TreeNodeFlags::SPAN_AVAIL_WIDTH)
+                .framed(true)
+                .build(|| {
+                    ui.checkbox("Lock In Place", &mut options.lock_root_motion);
+                    if ui.is_item_hovered() {
+                        ui.tooltip(|| {
+                            ui.text("Remove root translation and rotation from the skeleton");
+                        });
+                    }
+                    if ui.checkbox("Accumulate Loops", &mut options.accumulate_root_motion)
+                        && !options.accumulate_root_motion
+                    {
+                        mind_model.root_motion.reset();
+                    }
+                    ui.checkbox("Show Root Path", &mut options.show_root_path);
+                    if ui.button_with_size("Reset Position", [ui.content_region_avail()[0], 0.0f32])
+                    {
+                        mind_model.root_motion.reset();
+                    }
+                });
+
+            onion_skin(ui, options);
+            motion_trails(ui, options, mind_model);
+
+            inverse_kinematics(ui, mind_model);
+            compare(ui, options, mind_model);
+            animation_edit(ui, mind_model, model_ui, animations_path);
+        });
+
+    retarget(ui, mind_model);
+    posing(
+        ui,
+        options,
+        mind_model,
+        model_ui,
+        *export_as,
+        name,
+        animations_path,
+    );
+
+    ui.tree_node_config("Meshes")
+        .flags(imgui::TreeNodeFlags::SPAN_AVAIL_WIDTH)
+        .framed(true)
+        .build(|| {
+            for i in 0..mind_model.skin.meshes.len() {
+                let _meshes_id = ui.push_id_usize(i);
+                if options.render_mode == RenderMode::SubmeshId {
+                    let [r, g, b] = model::id_color(i as u32);
+                    ui.color_button("##submesh_color", [r, g, b, 1.0f32]);
+                    ui.same_line();
+                }
+                ui.checkbox(
+                    mind_model.skin.meshes[i].submesh.name.as_str(),
+                    &mut mind_model.show_meshes[i],
+                );
+                if mind_model.show_meshes[i] {
+                    ui.combo_simple_string(
+                        "##texture",
+                        &mut mind_model.textures_selecteds[i],
+                        &mind_model.textures_file_names,
+                    );
+                    imgui::Image::new(
+                        imgui::TextureId::new(
+                            mind_model.textures[mind_model.textures_selecteds[i]].id as usize,
+                        ),
+                        [64.0f32, 64.0f32],
+                    )
+                    .build(ui);
+                }
+            }
+        });
+
+    ui.tree_node_config("Export")
+        .flags(imgui::TreeNodeFlags::SPAN_AVAIL_WIDTH)
+        .framed(true)
+        .build(|| {
+            ui.radio_button("Export as gltf", export_as, 0);
+            ui.radio_button("Export as glb", export_as, 1);
+            if ui.button_with_size("Export Model", [ui.content_region_avail()[0], 0.0f32]) {
+                export::export_model(*export_as, name, mind_model);
+            }
+        });
+}
+
+const RENDER_MODES: [(&str, RenderMode); 7] = [
+    ("Textured", RenderMode::Textured),
+    ("Normals", RenderMode::Normals),
+    ("UV Checker", RenderMode::UvChecker),
+    ("Matcap", RenderMode::Matcap),
+    ("Depth", RenderMode::Depth),
+    ("Submesh ID", RenderMode::SubmeshId),
+    ("Joint Index", RenderMode::JointIndex),
+];
+
+const HEATMAP_STOPS: [[f32; 4]; 5] = [
+    [0.0f32, 0.0f32, 1.0f32, 1.0f32],
+    [0.0f32, 1.0f32, 1.0f32, 1.0f32],
+    [0.0f32, 1.0f32, 0.0f32, 1.0f32],
+    [1.0f32, 1.0f32, 0.0f32, 1.0f32],
+    [1.0f32, 0.0f32, 0.0f32, 1.0f32],
+];
+
+fn weight_heatmap(ui: &imgui::Ui, options: &mut OptionsJson, mind_model: &mut MindModel) {
+    ui.tree_node_config("Weight Heatmap")
+        .flags(imgui::TreeNodeFlags::SPAN_AVAIL_WIDTH)
+        .framed(true)
+        .build(|| {
+            ui.radio_button("Off", &mut options.heatmap_mode, HeatmapMode::Off);
+            ui.same_line();
+            ui.radio_button(
+                "Joint Weight",
+                &mut options.heatmap_mode,
+                HeatmapMode::JointWeight,
+            );
+            ui.radio_button(
+                "Influence Count",
+                &mut options.heatmap_mode,
+                HeatmapMode::InfluenceCount,
+            );
+            ui.same_line();
+            ui.radio_button(
+                "Weight Sum",
+                &mut options.heatmap_mode,
+                HeatmapMode::WeightSum,
+            );
+
+            let skin = &mind_model.skin;
+            match options.heatmap_mode {
+                HeatmapMode::Off => {}
+                HeatmapMode::JointWeight => {
+                    let joint_names: Vec<&str> = mind_model
+                        .skeleton
+                        .joints
+                        .iter()
+                        .map(|joint| joint.name.as_str())
+                        .collect();
+                    let mut selected_joint = mind_model.selected_joint.unwrap_or(0);
+                    ui.align_text_to_frame_padding();
+                    ui.text("Joint:     ");
+                    ui.same_line();
+                    if ui.combo_simple_string("##heatmap_joint", &mut selected_joint, &joint_names)
+                    {
+                        mind_model.selected_joint = Some(selected_joint);
+                    }
+
+                    heatmap_legend(ui, "0.0", "1.0");
+
+                    if let Some(joint) = mind_model.selected_joint {
+                        let weights: Vec<f32> = skin
+                            .influences
+                            .iter()
+                            .zip(skin.weights.iter())
+                            .map(|(influence, weight)| {
+                                [influence.x, influence.y, influence.z, influence.w]
+                                    .iter()
+                                    .zip(weight.to_array())
+                                    .filter(|(index, _)| **index as usize == joint)
+                                    .map(|(_, weight)| weight)
+                                    .sum::<f32>()
+                            })
+                            .filter(|weight| *weight > 0.0f32)
+                            .collect();
+                        ui.text(format!(
+                            "Influenced Vertices: {} / {}",
+                            weights.len(),
+                            skin.vertices.len()
+                        ));
+                        ui.text(format!(
+                            "Max Weight: {:.3}",
+                            weights.iter().copied().fold(0.0f32, f32::max)
+                        ));
+                    } else {
+                        ui.text_colored([1.0f32, 0.7f32, 0.2f32, 1.0f32], "No joint selected");
+                    }
+                }
+                HeatmapMode::InfluenceCount => {
+                    heatmap_legend(ui, "0", "4");
+
+                    let mut counts = [0usize; 5];
+                    for weight in skin.weights.iter() {
+                        counts[weight.to_array().iter().filter(|w| **w > 0.0f32).count()] += 1;
+                    }
+                    for (count, vertices) in counts.iter().enumerate() {
+                        ui.text(format!("{count} Influences: {vertices}"));
+                    }
+                }
+                HeatmapMode::WeightSum => {
+                    heatmap_legend(ui, "Sum 1.0", "Off By 0.1+");
+
+                    let unnormalized = skin
+                        .weights
+                        .iter()
+                        .filter(|weight| {
+                            (weight.to_array().iter().sum::<f32>() - 1.0f32).abs() > 0.001f32
+                        })
+                        .count();
+                    ui.text(format!(
+                        "Weights Not Summing To One: {} / {}",
+                        unnormalized,
+                        skin.vertices.len()
+                    ));
+                }
+            }
+        });
+}
+
+fn heatmap_legend(ui: &imgui::Ui, low: &str, high: &str) {
+    let draw_list = ui.get_window_draw_list();
+    let [x, y] = ui.cursor_screen_pos();
+    let width = ui.content_region_avail()[0];
+    let height = ui.text_line_height();
+    let segment = width / (HEATMAP_STOPS.len() - 1) as f32;
+
+    for (i, colors) in HEATMAP_STOPS.windows(2).enumerate() {
+        let left = x + segment * i as f32;
+        draw_list.add_rect_filled_multicolor(
+            [left, y],
+            [left + segment, y + height],
+            colors[0],
+            colors[1],
+            colors[1],
+            colors[0],
+        );
+    }
+    ui.dummy([width, height]);
+
+    ui.text(low);
+    ui.same_line_with_pos(width - ui.calc_text_size(high)[0]);
+    ui.text(high);
+}
+
+fn wireframe_overlay(ui: &imgui::Ui, options: &mut OptionsJson) {
+    ui.tree_node_config("Wireframe Overlay")
+        .flags(imgui::TreeNodeFlags::SPAN_AVAIL_WIDTH)
+        .framed(true)
+        .build(|| {
+            let overlay = &mut options.wireframe_overlay;
+
+            ui.checkbox("Show Edges", &mut overlay.show);
+            if overlay.show {
+                ui.align_text_to_frame_padding();
+                ui.text("Color:     ");
+                ui.same_line();
+                ui.color_edit4_config("##overlay_color", &mut overlay.color)
+                    .inputs(false)
+                    .build();
+
+                ui.align_text_to_frame_padding();
+                ui.text("Width:     ");
+                ui.same_line();
+                ui.slider_config("##overlay_width", 1.0f32, 5.0f32)
+                    .display_format("%.1f")
+                    .flags(imgui::SliderFlags::ALWAYS_CLAMP)
+                    .build(&mut overlay.width);
+            }
+
+            ui.checkbox("Show Vertices", &mut overlay.show_vertices);
+            if overlay.show_vertices {
+                ui.align_text_to_frame_padding();
+                ui.text("Color:     ");
+                ui.same_line();
+                ui.color_edit4_config("##vertex_color", &mut overlay.vertex_color)
+                    .inputs(false)
+                    .build();
+
+                ui.align_text_to_frame_padding();
+                ui.text("Size:      ");
+                ui.same_line();
+                ui.slider_config("##vertex_size", 1.0f32, 10.0f32)
+                    .display_format("%.1f")
+                    .flags(imgui::SliderFlags::ALWAYS_CLAMP)
+                    .build(&mut overlay.vertex_size);
+            }
+
+            ui.align_text_to_frame_padding();
+            ui.text("Offset:    ");
+            ui.same_line();
+            ui.slider_config("##overlay_offset", 0.0f32, 10.0f32)
+                .display_format("%.2f")
+                .flags(imgui::SliderFlags::ALWAYS_CLAMP)
+                .build(&mut overlay.depth_offset);
+            if ui.is_item_hovered() {
+                ui.tooltip(|| {
+                    ui.text("Pulls the overlay towards the camera to avoid z-fighting");
+                });
+            }
+        });
+}
+
+fn vertex_vectors(ui: &imgui::Ui, options: &mut OptionsJson, mind_model: &MindModel) {
+    ui.tree_node_config("Normals / Tangents")
+        .flags(imgui::TreeNodeFlags::SPAN_AVAIL_WIDTH)
+        .framed(true)
+        .build(|| {
+            let vertex_vectors = &mut options.vertex_vectors;
+
+            ui.checkbox("Show Normals", &mut vertex_vectors.show_normals);
+            if ui.is_item_hovered() {
+                ui.tooltip(|| {
+                    ui.text_colored([1.0f32, 0.0f32, 1.0f32, 1.0f32], "Unnormalized or NaN");
+                    ui.text_colored(
+                        [1.0f32, 1.0f32, 0.0f32, 1.0f32],
+                        "Facing against the triangles",
+                    );
+                });
+            }
+            ui.same_line();
+            ui.checkbox("Show Tangents", &mut vertex_vectors.show_tangents);
+            if ui.is_item_hovered() {
+                ui.tooltip(|| {
+                    ui.text("Derived from the UVs, skipped where they are degenerate");
+                });
+            }
+
+            ui.align_text_to_frame_padding();
+            ui.text("Length:    ");
+            ui.same_line();
+            ui.slider_config("##vectors_length", 0.1f32, 20.0f32)
+                .display_format("%.2f")
+                .flags(imgui::SliderFlags::LOGARITHMIC)
+                .build(&mut vertex_vectors.length);
+
+            for mesh in mind_model.skin.meshes.iter() {
+                let name = &mesh.submesh.name;
+                let mut included = !vertex_vectors.excluded_meshes.contains(name);
+                if ui.checkbox(format!("{name}##vectors_mesh"), &mut included) {
+                    if included {
+                        vertex_vectors
+                            .excluded_meshes
+                            .retain(|excluded| excluded != name);
+                    } else {
+                        vertex_vectors.excluded_meshes.push(name.to_owned());
+                    }
+                }
+            }
+        });
+}
+
+fn onion_skin(ui: &imgui::Ui, options: &mut OptionsJson) {
+    ui.tree_node_config("Onion Skin")
+        .flags(imgui::TreeNodeFlags::SPAN_AVAIL_WIDTH)
+        .framed(true)
+        .build(|| {
+            let onion_skin = &mut options.onion_skin;
+
+            ui.checkbox("Show Onion Skin", &mut onion_skin.show);
+            if ui.is_item_hovered() {
+                ui.tooltip(|| {
+                    ui.text("Draw translucent poses before and after the current time");
+                });
+            }
+            ui.checkbox("Bones Only", &mut onion_skin.bones_only);
+
+            ui.align_text_to_frame_padding();
+            ui.text("Frames:    ");
+            ui.same_line();
+            ui.slider_config("##onion_frames", 1usize, 10usize)
+                .flags(imgui::SliderFlags::ALWAYS_CLAMP)
+                .build(&mut onion_skin.frames);
+
+            ui.align_text_to_frame_padding();
+            ui.text("Step:      ");
+            ui.same_line();
+            ui.slider_config("##onion_step", 1usize, 30usize)
+                .flags(imgui::SliderFlags::ALWAYS_CLAMP)
+                .build(&mut onion_skin.step);
+            if ui.is_item_hovered() {
+                ui.tooltip(|| {
+                    ui.text("Animation frames between each onion skin");
+                });
+            }
+
+            ui.align_text_to_frame_padding();
+            ui.text("Before:    ");
+            ui.same_line();
+            ui.color_edit4_config("##onion_before_color", &mut onion_skin.before_color)
+                .inputs(false)
+                .build();
+
+            ui.align_text_to_frame_padding();
+            ui.text("After:     ");
+            ui.same_line();
+            ui.color_edit4_config("##onion_after_color", &mut onion_skin.after_color)
+                .inputs(false)
+                .build();
+        });
+}
+
+fn motion_trails(ui: &imgui::Ui, options: &mut OptionsJson, mind_model: &mut MindModel) {
+    ui.tree_node_config("Motion Trails")
+        .flags(imgui::TreeNodeFlags::SPAN_AVAIL_WIDTH)
+        .framed(true)
+        .build(|| {
+            let motion_trails = &mut options.motion_trails;
+
+            ui.checkbox("Show Motion Trails", &mut motion_trails.show);
+            if ui.is_item_hovered() {
+                ui.tooltip(|| {
+                    ui.text("Draw the path of the listed joints across the animation");
+                    ui.text("Samples before the playhead are darker, after are lighter");
+                });
+            }
+            ui.checkbox("Whole Clip", &mut motion_trails.whole_clip);
+            if !motion_trails.whole_clip {
+                ui.align_text_to_frame_padding();
+                ui.text("Window:    ");
+                ui.same_line();
+                ui.slider_config("##trail_window", 0.05f32, 5.0f32)
+                    .display_format("%.2fs")
+                    .flags(imgui::SliderFlags::ALWAYS_CLAMP)
+                    .build(&mut motion_trails.window);
+            }
+
+            ui.align_text_to_frame_padding();
+            ui.text("Width:     ");
+            ui.same_line();
+            ui.slider_config("##trail_width", 1.0f32, 8.0f32)
+                .display_format("%.1f")
+                .flags(imgui::SliderFlags::ALWAYS_CLAMP)
+                .build(&mut motion_trails.width);
+
+            let joint_names: Vec<&str> = mind_model
+                .skeleton
+                .joints
+                .iter()
+                .map(|joint| joint.name.as_str())
+                .collect();
+            let mut add_joint = usize::MAX;
+            ui.align_text_to_frame_padding();
+            ui.text("Add Joint: ");
+            ui.same_line();
+            ui.set_next_item_width(200.0f32);
+            if ui.combo_simple_string("##trail_joint", &mut add_joint, &joint_names) {
+                if let Some(name) = joint_names.get(add_joint) {
+                    if !motion_trails.joints.iter().any(|joint| joint == name) {
+                        motion_trails.joints.push(name.to_string());
+                    }
+                }
+            }
+            if let Some(selected_joint) = mind_model.selected_joint {
+                ui.same_line();
+                if ui.button("Add Selected") {
+                    let name = &mind_model.skeleton.joints[selected_joint].name;
+                    if !motion_trails.joints.contains(name) {
+                        motion_trails.joints.push(name.to_owned());
+                    }
+                }
+            }
+
+            let mut remove_joint: Option<usize> = None;
+            for (i, name) in motion_trails.joints.iter().enumerate() {
+                let _trail_id = ui.push_id_usize(i);
+                if confirm_delete_button(ui) {
+                    remove_joint = Some(i);
+                }
+                ui.same_line();
+                if joint_names.contains(&name.as_str()) {
+                    ui.text(name);
+                } else {
+                    ui.text_colored([1.0f32, 0.7f32, 0.2f32, 1.0f32], name);
+                    if ui.is_item_hovered() {
+                        ui.tooltip(|| {
+                            ui.text("Joint not found in skeleton");
+                        });
+                    }
+                }
+            }
+            if let Some(i) = remove_joint {
+                motion_trails.joints.remove(i);
+            }
+        });
+}
+
+fn joint_axes(ui: &imgui::Ui, options: &mut OptionsJson, mind_model: &MindModel) {
+    ui.tree_node_config("Joint Axes")
+        .flags(imgui::TreeNodeFlags::SPAN_AVAIL_WIDTH)
+        .framed(true)
+        .build(|| {
+            let joint_axes = &mut options.joint_axes;
+
+            ui.checkbox("Use Bind Pose", &mut joint_axes.bind_pose);
+            if ui.is_item_hovered() {
+                ui.tooltip(|| {
+                    ui.text("Show bind orientation even while animating");
+                });
+            }
+
+            ui.align_text_to_frame_padding();
+            ui.text("Size:      ");
+            ui.same_line();
+            ui.slider_config("##axes_size", 0.1f32, 50.0f32)
+                .display_format("%.2f")
+                .flags(imgui::SliderFlags::LOGARITHMIC)
+                .build(&mut joint_axes.size);
+
+            ui.align_text_to_frame_padding();
+            ui.text("Filter:    ");
+            ui.same_line();
+            ui.input_text("##axes_filter", &mut joint_axes.filter)
+                .hint("joint name")
+                .build();
+
+            ui.checkbox("Selected Subtree Only", &mut joint_axes.selected_subtree);
+            if joint_axes.selected_subtree && mind_model.selected_joint.is_none() {
+                ui.text_colored([1.0f32, 0.7f32, 0.2f32, 1.0f32], "No joint selected");
+            }
+        });
+}
+
+fn compare(ui: &imgui::Ui, options: &OptionsJson, mind_model: &mut MindModel) {
+    ui.tree_node_config("Compare")
+        .flags(imgui::TreeNodeFlags::SPAN_AVAIL_WIDTH)
+        .framed(true)
+        .build(|| {
+            let mut compare_names = vec!["None"];
+            compare_names.extend(mind_model.animations_file_names.iter().map(|x| x.as_str()));
+            let mut compare_selected = mind_model
+                .comparison
+                .as_ref()
+                .and_then(|comparison| {
+                    mind_model
+                        .animations_file_names
+                        .iter()
+                        .position(|name| *name == comparison.name)
+                })
+                .map_or(0, |position| position + 1);
+
+            ui.align_text_to_frame_padding();
+            ui.text("Compare To:");
+            ui.same_line();
+            if ui.combo_simple_string("##compare_to", &mut compare_selected, &compare_names) {
+                mind_model.comparison = compare_selected.checked_sub(1).map(|animation| {
+                    Comparison::new(
+                        mind_model.animations_file_names[animation].to_owned(),
+                        mind_model.animations[animation].clone(),
+                        &mind_model.skeleton,
+                    )
+                });
+            }
+
+            if ui.button_with_size(
+                "Load Animation File",
+                [ui.content_region_avail()[0], 0.0f32],
+            ) {
+                let path = FileDialog::new()
+                    .add_filter("Animation", &["anm"])
+                    .show_open_single_file()
+                    .unwrap();
+                if let Some(path) = path {
+                    let animation = Animation::read(&crate::read_to_u8(&path));
+                    mind_model.comparison = Some(Comparison::new(
+                        String::from(path.file_stem().unwrap().to_str().unwrap()),
+                        animation,
+                        &mind_model.skeleton,
+                    ));
+                }
+            }
+
+            let Some(comparison) = &mut mind_model.comparison else {
+                return;
+            };
+
+            ui.text(format!("Comparing with: {}", comparison.name));
+            let matched_tracks = comparison
+                .animation
+                .joints
+                .iter()
+                .filter(|joint| {
+                    mind_model
+                        .skeleton
+                        .joints
+                        .iter()
+                        .any(|skeleton_joint| skeleton_joint.hash == joint.hash)
+                })
+                .count();
+            if matched_tracks == 0 {
+                ui.text_colored(
+                    [1.0f32, 0.7f32, 0.2f32, 1.0f32],
+                    "Animation has no tracks for this skeleton",
+                );
+            }
+
+            ui.checkbox("Show Ghost", &mut comparison.show_ghost);
+            ui.same_line();
+            ui.color_edit4_config("##ghost_color", &mut comparison.ghost_color)
+                .inputs(false)
+                .build();
+
+            ui.text("Sort By:");
+            ui.same_line();
+            if ui.radio_button("Position", &mut comparison.metric, CompareMetric::Position)
+                | ui.radio_button("Angle", &mut comparison.metric, CompareMetric::Angle)
+            {
+                comparison.sort();
+            }
+
+            let animation = &mind_model.animations[mind_model.animation_selected];
+            let frame = comparison.frame(crate::evaluated_time(options, animation));
+            let max_value =
+                comparison
+                    .joints
+                    .first()
+                    .map_or(0.0f32, |joint| match comparison.metric {
+                        CompareMetric::Position => joint.max_position,
+                        CompareMetric::Angle => joint.max_angle,
+                    });
+
+            ui.child_window("##heat_list")
+                .size([0.0f32, 250.0f32])
+                .border(true)
+                .build(|| {
+                    for joint_difference in comparison.joints.iter() {
+                        let joint = joint_difference.joint;
+                        let _joint_id = ui.push_id_usize(joint);
+
+                        let (value, overlay) = match comparison.metric {
+                            CompareMetric::Position => (
+                                joint_difference.max_position,
+                                format!("{:.4}", joint_difference.max_position),
+                            ),
+                            CompareMetric::Angle => (
+                                joint_difference.max_angle,
+                                format!("{:.3} deg", joint_difference.max_angle),
+                            ),
+                        };
+                        let heat = if max_value > 0.0f32 {
+                            value / max_value
+                        } else {
+                            0.0f32
+                        };
+
+                        let selected = mind_model.selected_joint == Some(joint);
+                        if ui
+                            .selectable_config(&mind_model.skeleton.joints[joint].name)
+                            .selected(selected)
+                            .size([150.0f32, 0.0f32])
+                            .build()
+                        {
+                            mind_model.selected_joint = if selected { None } else { Some(joint) };
+                        }
+                        if ui.is_item_hovered() {
+                            ui.tooltip(|| {
+                                ui.text(format!(
+                                    "Frame {}: {:.4} / {:.3} deg",
+                                    frame,
+                                    comparison.position_differences[joint][frame],
+                                    comparison.angle_differences[joint][frame]
+                                ));
+                                ui.text(format!(
+                                    "Max: {:.4} / {:.3} deg",
+                                    joint_difference.max_position, joint_difference.max_angle
+                                ));
+                                ui.text(format!(
+                                    "Mean: {:.4} / {:.3} deg",
+                                    joint_difference.mean_position, joint_difference.mean_angle
+                                ));
+                            });
+                        }
+                        ui.same_line();
+                        let _heat_color = ui.push_style_color(
+                            imgui::StyleColor::PlotHistogram,
+                            [heat, 1.0f32 - heat, 0.0f32, 1.0f32],
+                        );
+                        imgui::ProgressBar::new(heat)
+                            .overlay_text(&overlay)
+                            .size([-1.0f32, 0.0f32])
+                            .build(ui);
+                    }
+                });
+
+            if ui.button_with_size("Remove Comparison", [ui.content_region_avail()[0], 0.0f32]) {
+                mind_model.comparison = None;
+            }
+        });
+}
+
+pub struct AnimationEdit {
+    pub trim: [f32; 2],
+    pub fps: f32,
+    pub time_scale: f32,
+    pub concatenate: usize,
+    pub mirror_axis: MirrorAxis,
+    pub save_name: String,
+}
+
+impl AnimationEdit {
+    pub fn new() -> AnimationEdit {
+        AnimationEdit {
+            trim: [0.0f32, 0.0f32],
+            fps: 30.0f32,
+            time_scale: 1.0f32,
+            concatenate: 0,
+            mirror_axis: MirrorAxis::X,
+            save_name: String::new(),
+        }
+    }
+}
+
+fn animation_edit(
+    ui: &imgui::Ui,
+    mind_model: &mut MindModel,
+    model_ui: &mut ModelUi,
+    animations_path: &str,
+) {
+    ui.tree_node_config("Edit")
+        .flags(imgui::TreeNodeFlags::SPAN_AVAIL_WIDTH)
+        .framed(true)
+        .build(|| {
+            let animation_selected = mind_model.animation_selected;
+            let source_name = mind_model.animations_file_names[animation_selected].to_owned();
+            let animation = &mind_model.animations[animation_selected];
+            let edit = &mut model_ui.animation_edit;
+
+            ui.text(format!(
+                "Source: {} - {:.3} fps - {:.5}s",
+                source_name, animation.fps, animation.duration
+            ));
+
+            let mut result: Option<(Animation, &str)> = None;
+
+            if edit.trim[1] <= edit.trim[0] {
+                edit.trim = [0.0f32, animation.duration];
+            }
+
+            ui.align_text_to_frame_padding();
+            ui.text("Range:     ");
+            ui.same_line();
+            ui.set_next_item_width(200.0f32);
+            ui.input_float2("##trim", &mut edit.trim)
+                .display_format("%.5f")
+                .build();
+            ui.same_line();
+            if ui.button("Trim") {
+                result = Some((animation.trim(edit.trim[0], edit.trim[1]), "trim"));
+            }
+
+            ui.align_text_to_frame_padding();
+            ui.text("FPS:       ");
+            ui.same_line();
+            ui.set_next_item_width(200.0f32);
+            ui.input_float("##fps", &mut edit.fps)
+                .display_format("%.3f")
+                .build();
+            edit.fps = edit.fps.max(1.0f32);
+            ui.same_line();
+            if ui.button("Resample") {
+                result = Some((animation.resample(edit.fps), "resample"));
+            }
+
+            ui.align_text_to_frame_padding();
+            ui.text("Speed:     ");
+            ui.same_line();
+            ui.set_next_item_width(200.0f32);
+            ui.input_float("##time_scale", &mut edit.time_scale)
+                .display_format("%.3f")
+                .build();
+            edit.time_scale = edit.time_scale.max(0.001f32);
+            ui.same_line();
+            if ui.button("Time Scale") {
+                result = Some((animation.time_scale(edit.time_scale), "scale"));
+            }
+
+            ui.align_text_to_frame_padding();
+            ui.text("Append:    ");
+            ui.same_line();
+            ui.set_next_item_width(200.0f32);
+            ui.combo_simple_string(
+                "##concatenate",
+                &mut edit.concatenate,
+                &mind_model.animations_file_names,
+            );
+            ui.same_line();
+            if ui.button("Concatenate") {
+                if let Some(other) = mind_model.animations.get(edit.concatenate) {
+                    result = Some((animation.concatenate(other), "concat"));
+                }
+            }
+
+            if ui.button_with_size("Reverse", [ui.content_region_avail()[0], 0.0f32]) {
+                result = Some((animation.reverse(), "reverse"));
+            }
+
+            ui.align_text_to_frame_padding();
+            ui.text("Mirror:    ");
+            ui.same_line();
+            ui.radio_button("X##mirror_x", &mut edit.mirror_axis, MirrorAxis::X);
+            ui.same_line();
+            ui.radio_button("Y##mirror_y", &mut edit.mirror_axis, MirrorAxis::Y);
+            ui.same_line();
+            ui.radio_button("Z##mirror_z", &mut edit.mirror_axis, MirrorAxis::Z);
+            ui.same_line();
+            let mirror_pairs = MirrorPairs::new(&mind_model.skeleton);
+            if ui.button("Mirror L/R") {
+                result = Some((
+                    mirror::mirror_animation(
+                        animation,
+                        &mind_model.skeleton,
+                        &mirror_pairs,
+                        edit.mirror_axis,
+                    ),
+                    "mirror",
+                ));
+            }
+            if ui.is_item_hovered() {
+                ui.tooltip(|| {
+                    ui.text("Reflect across the plane normal to the axis and swap L_/R_ joints");
+                });
+            }
+            ui.text(format!("{} paired joints", mirror_pairs.paired_count()));
+            if !mirror_pairs.unpaired.is_empty() {
+                ui.tree_node_config(&format!(
+                    "Unpaired Joints ({})###unpaired_joints",
+                    mirror_pairs.unpaired.len()
+                ))
+                .flags(imgui::TreeNodeFlags::SPAN_AVAIL_WIDTH)
+                .build(|| {
+                    for name in mirror_pairs.unpaired.iter() {
+                        ui.text_colored([1.0f32, 0.7f32, 0.2f32, 1.0f32], name);
+                    }
+                });
+            }
+
+            ui.separator();
+
+            ui.align_text_to_frame_padding();
+            ui.text("File Name: ");
+            ui.same_line();
+            ui.set_next_item_width(200.0f32);
+            ui.input_text("##save_name", &mut edit.save_name)
+                .hint(&source_name)
+                .build();
+            ui.same_line();
+            if ui.button("Save As ANM") {
+                let file_name = if edit.save_name.is_empty() {
+                    &source_name
+                } else {
+                    &edit.save_name
+                };
+                let path = Path::new(animations_path).join(format!("{file_name}.anm"));
+                if path.exists() {
+                    println!("Animation already exists: {}", path.to_str().unwrap());
+                } else {
+                    animation.write(&path);
+                }
+            }
+            if ui.is_item_hovered() {
+                ui.tooltip(|| {
+                    ui.text("Save selected animation next to the original animations");
+                });
+            }
+
+            if let Some((animation, suffix)) = result {
+                let name = format!("{source_name}_{suffix}");
+                model_ui.animation_edit.save_name = name.to_owned();
+                mind_model.animation_selected = crate::add_animation(mind_model, animation, name);
+            }
+        });
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum GizmoMode {
+    Rotate,
+    Translate,
+}
+
+pub struct PoseGizmo {
+    pub mode: GizmoMode,
+    pub drag_axis: Option<usize>,
+    pub last_mouse: [f32; 2],
+    pub preset_name: String,
+}
+
+impl PoseGizmo {
+    pub fn new() -> PoseGizmo {
+        PoseGizmo {
+            mode: GizmoMode::Rotate,
+            drag_axis: None,
+            last_mouse: [0.0f32, 0.0f32],
+            preset_name: String::new(),
+        }
+    }
+}
+
+const GIZMO_COLORS: [[f32; 4]; 3] = [
+    [0.9f32, 0.2f32, 0.2f32, 1.0f32],
+    [0.2f32, 0.9f32, 0.2f32, 1.0f32],
+    [0.2f32, 0.4f32, 1.0f32, 1.0f32],
+];
+const GIZMO_ACTIVE_COLOR: [f32; 4] = [1.0f32, 0.9f32, 0.2f32, 1.0f32];
+const GIZMO_SEGMENTS: usize = 48;
+
+fn posing(
+    ui: &imgui::Ui,
+    options: &mut OptionsJson,
+    mind_model: &mut MindModel,
+    model_ui: &mut ModelUi,
+    export_as: u8,
+    name: &String,
+    animations_path: &str,
+) {
+    ui.tree_node_config("Posing")
+        .flags(imgui::TreeNodeFlags::SPAN_AVAIL_WIDTH)
+        .framed(true)
+        .build(|| {
+            let mut changed = ui.checkbox("Enable Posing", &mut mind_model.posing.enabled);
+            if changed && mind_model.posing.enabled && mind_model.selected_joint.is_none() {
+                mind_model.selected_joint = Some(0);
+            }
+            if ui.is_item_hovered() {
+                ui.tooltip(|| {
+                    ui.text("Drag the gizmo of the selected joint to pose it");
+                    ui.text("Offsets are applied on top of the bind or animated pose");
+                });
+            }
+
+            let joint_names: Vec<&str> = mind_model
+                .skeleton
+                .joints
+                .iter()
+                .map(|joint| joint.name.as_str())
+                .collect();
+            let mut selected_joint = mind_model.selected_joint.unwrap_or(0);
+            ui.align_text_to_frame_padding();
+            ui.text("Joint:     ");
+            ui.same_line();
+            if ui.combo_simple_string("##pose_joint", &mut selected_joint, &joint_names) {
+                mind_model.selected_joint = Some(selected_joint);
+            }
+
+            let gizmo = &mut model_ui.pose_gizmo;
+            ui.align_text_to_frame_padding();
+            ui.text("Gizmo:     ");
+            ui.same_line();
+            ui.radio_button("Rotate", &mut gizmo.mode, GizmoMode::Rotate);
+            ui.same_line();
+            ui.radio_button("Translate", &mut gizmo.mode, GizmoMode::Translate);
+
+            if let Some(joint) = mind_model.selected_joint {
+                let offset = &mut mind_model.posing.offsets[joint];
+
+                let mut translation = offset.translation.to_array();
+                ui.align_text_to_frame_padding();
+                ui.text("Translate: ");
+                ui.same_line();
+                if ui
+                    .input_float3("##pose_translation", &mut translation)
+                    .display_format("%.3f")
+                    .build()
+                {
+                    offset.translation = glam::Vec3::from_array(translation);
+                    changed = true;
+                }
+
+                let (x, y, z) = offset.rotation.to_euler(glam::EulerRot::XYZ);
+                let mut rotation = [x.to_degrees(), y.to_degrees(), z.to_degrees()];
+                ui.align_text_to_frame_padding();
+                ui.text("Rotate:    ");
+                ui.same_line();
+                if ui
+                    .input_float3("##pose_rotation", &mut rotation)
+                    .display_format("%.2f")
+                    .build()
+                {
+                    offset.rotation = glam::Quat::from_euler(
+                        glam::EulerRot::XYZ,
+                        rotation[0].to_radians(),
+                        rotation[1].to_radians(),
+                        rotation[2].to_radians(),
+                    );
+                    changed = true;
+                }
+
+                if ui.button("Reset Joint") {
+                    *offset = JointPose::IDENTITY;
+                    changed = true;
+                }
+                ui.same_line();
+            }
+            if ui.button("Reset All") {
+                mind_model.posing.reset();
+                changed = true;
+            }
+
+            ui.separator();
+
+            let gizmo = &mut model_ui.pose_gizmo;
+            ui.align_text_to_frame_padding();
+            ui.text("Pose Name: ");
+            ui.same_line();
+            ui.set_next_item_width(200.0f32);
+            ui.input_text("##preset_name", &mut gizmo.preset_name)
+                .hint("pose")
+                .build();
+            let pose_name = if gizmo.preset_name.is_empty() {
+                String::from("pose")
+            } else {
+                gizmo.preset_name.to_owned()
+            };
+            ui.same_line();
+            if ui.button("Save Preset") {
+                let preset = mind_model
+                    .skeleton
+                    .joints
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| mind_model.posing.is_posed(*i))
+                    .map(|(i, joint)| {
+                        let offset = &mind_model.posing.offsets[i];
+                        (
+                            joint.name.to_owned(),
+                            JointOffsetJson {
+                                translation: offset.translation.to_array(),
+                                rotation: offset.rotation.to_array(),
+                            },
+                        )
+                    })
+                    .collect();
+                options.pose_presets.insert(pose_name.to_owned(), preset);
+            }
+
+            let mut load_preset: Option<String> = None;
+            let mut delete_preset: Option<String> = None;
+            for (i, (preset_name, preset)) in options.pose_presets.iter().enumerate() {
+                let _preset_id = ui.push_id_usize(i);
+                if confirm_delete_button(ui) {
+                    delete_preset = Some(preset_name.to_owned());
+                }
+                ui.same_line();
+                if ui.button("Load") {
+                    load_preset = Some(preset_name.to_owned());
+                }
+                ui.same_line();
+                ui.text(format!("{} ({} joints)", preset_name, preset.len()));
+            }
+            if let Some(preset) = load_preset.and_then(|name| options.pose_presets.get(&name)) {
+                mind_model.posing.reset();
+                for (i, joint) in mind_model.skeleton.joints.iter().enumerate() {
+                    if let Some(offset) = preset.get(&joint.name) {
+                        mind_model.posing.offsets[i] = JointPose {
+                            translation: glam::Vec3::from_array(offset.translation),
+                            rotation: glam::Quat::from_array(offset.rotation).normalize(),
+                            scale: glam::Vec3::ONE,
+                        };
+                    }
+                }
+                mind_model.posing.enabled = true;
+                changed = true;
+            }
+            if let Some(preset_name) = delete_preset {
+                options.pose_presets.remove(&preset_name);
+            }
+            if changed {
+                mind_model.pose_revision += 1;
+            }
+
+            ui.separator();
+
+            let skinned = options.use_animation || mind_model.posing.enabled;
+            if ui.button("Export Pose ANM") {
+                let pose = posing::current_pose(
+                    &mind_model.joints_transforms,
+                    &mind_model.skeleton,
+                    skinned,
+                );
+                let path = Path::new(animations_path).join(format!("{name}_{pose_name}.anm"));
+                if path.exists() {
+                    println!("Animation already exists: {}", path.to_str().unwrap());
+                } else {
+                    posing::pose_animation(&pose, &mind_model.skeleton).write(&path);
+                }
+            }
+            if ui.is_item_hovered() {
+                ui.tooltip(|| {
+                    ui.text("Save the current pose as a single frame animation");
+                });
+            }
+            ui.same_line();
+            if ui.button("Export Pose glTF") {
+                let pose = posing::current_pose(
+                    &mind_model.joints_transforms,
+                    &mind_model.skeleton,
+                    skinned,
+                );
+                export::export_pose(export_as, name, mind_model, &pose_name, &pose);
+            }
+            if ui.is_item_hovered() {
+                ui.tooltip(|| {
+                    ui.text("Export the model with the current pose as its rest pose");
+                });
+            }
+
+            if ui.button_with_size(
+                "Rebind To Current Pose",
+                [ui.content_region_avail()[0], 0.0f32],
+            ) {
+                mind_model.rebind_pose = true;
+            }
+            if ui.is_item_hovered() {
+                ui.tooltip(|| {
+                    ui.text("Bake the posed mesh and make the current pose the new bind pose");
+                    ui.text("Use Export Model to save the result");
+                });
+            }
+        });
+}
+
+pub fn pose_gizmo(
+    ui: &imgui::Ui,
+    mind_model: &mut MindModel,
+    model_ui: &mut ModelUi,
+    projection_view_matrix: &glam::Mat4,
+) -> bool {
+    let Some(joint) = mind_model.selected_joint else {
+        return false;
+    };
+
+    let display_size = ui.io().display_size;
+    let project =
+        |position: glam::Vec3| project_to_screen(projection_view_matrix, display_size, position);
+
+    let skeleton_joint = &mind_model.skeleton.joints[joint];
+    let global_matrix = mind_model.joints_transforms[joint] * skeleton_joint.global_matrix;
+    let parent_matrix = if skeleton_joint.parent_id != -1 {
+        let parent = skeleton_joint.parent_id as usize;
+        mind_model.joints_transforms[parent] * mind_model.skeleton.joints[parent].global_matrix
+    } else {
+        glam::Mat4::IDENTITY
+    };
+
+    let center = joint_position(&global_matrix);
+    let clip_w = (*projection_view_matrix * center.extend(1.0f32)).w;
+    let Some(screen_center) = project(center) else {
+        return false;
+    };
+    let size = 0.12f32 * clip_w;
+
+    let mouse = glam::Vec2::from_array(ui.io().mouse_pos);
+    let gizmo = &mut model_ui.pose_gizmo;
+    let axes: [glam::Vec3; 3] =
+        match gizmo.mode {
+            GizmoMode::Rotate => glam::Vec3::AXES
+                .map(|axis| global_matrix.transform_vector3(axis).normalize_or_zero()),
+            GizmoMode::Translate => glam::Vec3::AXES
+                .map(|axis| parent_matrix.transform_vector3(axis).normalize_or_zero()),
+        };
+
+    let shapes: Vec<Vec<glam::Vec2>> = (0..3)
+        .map(|i| match gizmo.mode {
+            GizmoMode::Rotate => {
+                let u = axes[(i + 1) % 3];
+                let v = axes[(i + 2) % 3];
+                (0..=GIZMO_SEGMENTS)
+                    .filter_map(|segment| {
+                        let angle = segment as f32 / GIZMO_SEGMENTS as f32 * std::f32::consts::TAU;
+                        project(center + (u * angle.cos() + v * angle.sin()) * size)
+                    })
+                    .collect()
+            }
+            GizmoMode::Translate => [center, center + axes[i] * size]
+                .into_iter()
+                .filter_map(project)
+                .collect(),
+        })
+        .collect();
+
+    let distance_to_segment = |a: glam::Vec2, b: glam::Vec2| {
+        let ab = b - a;
+        let t = ((mouse - a).dot(ab) / ab.length_squared().max(f32::EPSILON)).clamp(0.0f32, 1.0f32);
+        mouse.distance(a + ab * t)
+    };
+    let hovered_axis = (0..3)
+        .filter_map(|i| {
+            shapes[i]
+                .windows(2)
+                .map(|segment| distance_to_segment(segment[0], segment[1]))
+                .reduce(f32::min)
+                .filter(|distance| *distance < 6.0f32)
+                .map(|distance| (i, distance))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i);
+
+    if !ui.is_mouse_down(imgui::MouseButton::Left) {
+        gizmo.drag_axis = None;
+    } else if ui.is_mouse_clicked(imgui::MouseButton::Left) && no_window_hovered() {
+        gizmo.drag_axis = hovered_axis;
+    }
+
+    if let Some(axis) = gizmo.drag_axis {
+        let last_mouse = glam::Vec2::from_array(gizmo.last_mouse);
+        if mouse != last_mouse {
+            mind_model.pose_revision += 1;
+        }
+        let offset = &mut mind_model.posing.offsets[joint];
+        match gizmo.mode {
+            GizmoMode::Rotate => {
+                let from = last_mouse - screen_center;
+                let to = mouse - screen_center;
+                let u = project(center + axes[(axis + 1) % 3] * size);
+                let v = project(center + axes[(axis + 2) % 3] * size);
+                if let (Some(u), Some(v)) = (u, v) {
+                    let direction = (u - screen_center).perp_dot(v - screen_center).signum();
+                    let delta = from.angle_between(to);
+                    if delta.is_finite() {
+                        offset.rotation = (offset.rotation
+                            * glam::Quat::from_axis_angle(
+                                glam::Vec3::AXES[axis],
+                                delta * direction,
+                            ))
+                        .normalize();
+                    }
+                }
+            }
+            GizmoMode::Translate => {
+                if let [start, end] = shapes[axis][..] {
+                    let screen_axis = end - start;
+                    let amount = (mouse - last_mouse).dot(screen_axis)
+                        / screen_axis.length_squared().max(f32::EPSILON)
+                        * size;
+                    offset.translation += parent_matrix
+                        .inverse()
+                        .transform_vector3(axes[axis] * amount);
+                }
+            }
+        }
+    }
+    gizmo.last_mouse = mouse.to_array();
+
+    let draw_list = ui.get_background_draw_list();
+    for (i, shape) in shapes.iter().enumerate() {
+        let color = if gizmo.drag_axis.or(hovered_axis) == Some(i) {
+            GIZMO_ACTIVE_COLOR
+        } else {
+            GIZMO_COLORS[i]
+        };
+        let points: Vec<[f32; 2]> = shape.iter().map(|point| point.to_array()).collect();
+        draw_list
+            .add_polyline(points, color)
+            .thickness(2.0f32)
+            .build();
+        if gizmo.mode == GizmoMode::Translate {
+            if let Some(end) = shape.last() {
+                draw_list
+                    .add_circle(end.to_array(), 4.0f32, color)
+                    .filled(true)
+                    .build();
+            }
+        }
+    }
+    draw_list
+        .add_circle(screen_center.to_array(), 3.0f32, GIZMO_ACTIVE_COLOR)
+        .filled(true)
+        .build();
+
+    gizmo.drag_axis.is_some() || (hovered_axis.is_some() && no_window_hovered())
+}
+
+fn inverse_kinematics(ui: &imgui::Ui, mind_model: &mut MindModel) {
+    ui.tree_node_config("IK")
+        .flags(imgui::TreeNodeFlags::SPAN_AVAIL_WIDTH)
+        .framed(true)
+        .build(|| {
+            let joint_names: Vec<&str> = mind_model
+                .skeleton
+                .joints
+                .iter()
+                .map(|joint| joint.name.as_str())
+                .collect();
+            let ik = &mut mind_model.ik;
+
+            let mut changed = ui.checkbox("Two-Bone IK", &mut ik.two_bone);
+            if ui.is_item_hovered() {
+                ui.tooltip(|| {
+                    ui.text("Solve the end joint and its two parents towards the target");
+                    ui.text("Drag the target and pole handles in the viewport");
+                });
+            }
+
+            let mut end_joint = ik.end_joint.unwrap_or(0);
+            ui.align_text_to_frame_padding();
+            ui.text("End Joint: ");
+            ui.same_line();
+            if ui.combo_simple_string("##ik_end_joint", &mut end_joint, &joint_names) {
+                ik.set_end_joint(
+                    end_joint,
+                    &mind_model.joints_transforms,
+                    &mind_model.skeleton,
+                );
+                changed = true;
+            }
+            match ik.chain(&mind_model.skeleton) {
+                Some(chain) => ui.text(format!(
+                    "Chain: {} > {} > {}",
+                    joint_names[chain[0]], joint_names[chain[1]], joint_names[chain[2]]
+                )),
+                None if ik.end_joint.is_some() => ui.text_colored(
+                    [1.0f32, 0.7f32, 0.2f32, 1.0f32],
+                    "End joint needs two parents",
+                ),
+                None => {}
+            }
+
+            let mut target = ik.target.to_array();
+            ui.align_text_to_frame_padding();
+            ui.text("Target:    ");
+            ui.same_line();
+            if ui
+                .input_float3("##ik_target", &mut target)
+                .display_format("%.3f")
+                .build()
+            {
+                ik.target = glam::Vec3::from_array(target);
+                changed = true;
+            }
+
+            let mut pole = ik.pole.to_array();
+            ui.align_text_to_frame_padding();
+            ui.text("Pole:      ");
+            ui.same_line();
+            if ui
+                .input_float3("##ik_pole", &mut pole)
+                .display_format("%.3f")
+                .build()
+            {
+                ik.pole = glam::Vec3::from_array(pole);
+                changed = true;
+            }
+
+            if let Some(end_joint) = ik.end_joint {
+                if ui.button_with_size("Reset Target", [ui.content_region_avail()[0], 0.0f32]) {
+                    ik.set_end_joint(
+                        end_joint,
+                        &mind_model.joints_transforms,
+                        &mind_model.skeleton,
+                    );
+                    changed = true;
+                }
+            }
+
+            ui.separator();
+
+            changed |= ui.checkbox("Look-At", &mut ik.look_at);
+            if ui.is_item_hovered() {
+                ui.tooltip(|| {
+                    ui.text("Rotate the joint so its aim axis points at the target");
+                });
+            }
+
+            let mut look_at_joint = ik.look_at_joint.unwrap_or(0);
+            ui.align_text_to_frame_padding();
+            ui.text("Joint:     ");
+            ui.same_line();
+            if ui.combo_simple_string("##look_at_joint", &mut look_at_joint, &joint_names) {
+                ik.set_look_at_joint(
+                    look_at_joint,
+                    &mind_model.joints_transforms,
+                    &mind_model.skeleton,
+                );
+                changed = true;
+            }
+
+            let aim_axes: Vec<&str> = ik::AIM_AXES.iter().map(|(name, _)| *name).collect();
+            ui.align_text_to_frame_padding();
+            ui.text("Aim Axis:  ");
+            ui.same_line();
+            changed |= ui.combo_simple_string("##aim_axis", &mut ik.aim_axis, &aim_axes);
+
+            let mut look_at_target = ik.look_at_target.to_array();
+            ui.align_text_to_frame_padding();
+            ui.text("Target:    ");
+            ui.same_line();
+            if ui
+                .input_float3("##look_at_target", &mut look_at_target)
+                .display_format("%.3f")
+                .build()
+            {
+                ik.look_at_target = glam::Vec3::from_array(look_at_target);
+                changed = true;
+            }
+
+            if let Some(look_at_joint) = ik.look_at_joint {
+                if ui.button_with_size(
+                    "Reset Look-At Target",
+                    [ui.content_region_avail()[0], 0.0f32],
+                ) {
+                    ik.set_look_at_joint(
+                        look_at_joint,
+                        &mind_model.joints_transforms,
+                        &mind_model.skeleton,
+                    );
+                    changed = true;
+                }
+            }
+
+            if changed {
+                mind_model.pose_revision += 1;
+            }
+        });
+}
+
+pub fn ik_gizmo(
+    ui: &imgui::Ui,
+    mind_model: &mut MindModel,
+    projection_view_matrix: &glam::Mat4,
+) -> bool {
+    let display_size = ui.io().display_size;
+    let project =
+        |position: glam::Vec3| project_to_screen(projection_view_matrix, display_size, position);
+    let joint_position = |joint: usize| {
+        joint_position(
+            &(mind_model.joints_transforms[joint]
+                * mind_model.skeleton.joints[joint].global_matrix),
+        )
+    };
+
+    let ik = &mind_model.ik;
+    let mut handles: Vec<(IkHandle, glam::Vec3, glam::Vec3, [f32; 4])> = vec![];
+    if ik.two_bone {
+        if let Some([_, middle, end]) = ik.chain(&mind_model.skeleton) {
+            handles.push((
+                IkHandle::Target,
+                ik.target,
+                joint_position(end),
+                [0.2f32, 0.9f32, 0.9f32, 1.0f32],
+            ));
+            handles.push((
+                IkHandle::Pole,
+                ik.pole,
+                joint_position(middle),
+                [0.9f32, 0.3f32, 0.9f32, 1.0f32],
+            ));
+        }
+    }
+    if ik.look_at {
+        if let Some(joint) = ik.look_at_joint {
+            handles.push((
+                IkHandle::LookAt,
+                ik.look_at_target,
+                joint_position(joint),
+                [1.0f32, 0.8f32, 0.2f32, 1.0f32],
+            ));
+        }
+    }
+
+    let mouse = glam::Vec2::from_array(ui.io().mouse_pos);
+    let hovered_handle = handles
+        .iter()
+        .filter_map(|(handle, position, _, _)| {
+            project(*position)
+                .map(|screen| (*handle, screen.distance(mouse)))
+                .filter(|(_, distance)| *distance < 8.0f32)
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(handle, _)| handle);
+
+    let ik = &mut mind_model.ik;
+    if !ui.is_mouse_down(imgui::MouseButton::Left) {
+        ik.drag_handle = None;
+    } else if ui.is_mouse_clicked(imgui::MouseButton::Left) && no_window_hovered() {
+        ik.drag_handle = hovered_handle;
+    }
+
+    if let Some(handle) = ik.drag_handle {
+        let position = match handle {
+            IkHandle::Target => &mut ik.target,
+            IkHandle::Pole => &mut ik.pole,
+            IkHandle::LookAt => &mut ik.look_at_target,
+        };
+        let dragged = unproject_from_screen(projection_view_matrix, display_size, mouse, *position);
+        if dragged != *position {
+            *position = dragged;
+            mind_model.pose_revision += 1;
+        }
+    }
+
+    let draw_list = ui.get_background_draw_list();
+    for (handle, _, joint, color) in handles.iter() {
+        let position = match handle {
+            IkHandle::Target => ik.target,
+            IkHandle::Pole => ik.pole,
+            IkHandle::LookAt => ik.look_at_target,
+        };
+        let (Some(screen), Some(joint_screen)) = (project(position), project(*joint)) else {
+            continue;
+        };
+        let color = if ik.drag_handle.or(hovered_handle) == Some(*handle) {
+            GIZMO_ACTIVE_COLOR
+        } else {
+            *color
+        };
+        draw_list
+            .add_line(joint_screen.to_array(), screen.to_array(), color)
+            .thickness(1.0f32)
+            .build();
+        draw_list
+            .add_circle(screen.to_array(), 6.0f32, color)
+            .filled(true)
+            .build();
+    }
+
+    ik.drag_handle.is_some() || (hovered_handle.is_some() && no_window_hovered())
+}
+
+fn animation_layers(ui: &imgui::Ui, mind_model: &mut MindModel) {
+    ui.tree_node_config("Layers")
+        .flags(imgui::TreeNodeFlags::SPAN_AVAIL_WIDTH)
+        .framed(true)
+        .build(|| {
+            let mut mask_names = vec!["All Joints"];
+            mask_names.extend(
+                mind_model
+                    .skeleton
+                    .joints
+                    .iter()
+                    .map(|joint| joint.name.as_str()),
+            );
+
+            let mut changed = false;
+            let mut layer_removed = None;
+            for (i, layer) in mind_model.animation_layers.iter_mut().enumerate() {
+                let _layer_id = ui.push_id_usize(i);
+
+                if confirm_delete_button(ui) {
+                    layer_removed = Some(i);
+                }
+                ui.same_line();
+                ui.text(format!("Layer {i}"));
+
+                ui.align_text_to_frame_padding();
+                ui.text("Animation: ");
+                ui.same_line();
+                changed |= ui.combo_simple_string(
+                    "##layer_animation",
+                    &mut layer.animation,
+                    &mind_model.animations_file_names,
+                );
+
+                ui.align_text_to_frame_padding();
+                ui.text("Weight:    ");
+                ui.same_line();
+                changed |= ui
+                    .slider_config("##layer_weight", 0.0f32, 1.0f32)
+                    .display_format("%.3f")
+                    .flags(imgui::SliderFlags::ALWAYS_CLAMP)
+                    .build(&mut layer.weight);
+
+                ui.align_text_to_frame_padding();
+                ui.text("Mask:      ");
+                ui.same_line();
+                let mut mask_selected = layer.mask.map_or(0, |mask| mask + 1);
+                if ui.combo_simple_string("##layer_mask", &mut mask_selected, &mask_names) {
+                    layer.mask = mask_selected.checked_sub(1);
+                    changed = true;
+                }
+                if ui.is_item_hovered() {
+                    ui.tooltip(|| {
+                        ui.text("Apply layer only to this joint and its children");
+                    });
+                }
+
+                changed |= ui.checkbox("Additive", &mut layer.additive);
+
+                ui.separator();
+            }
+
+            if let Some(i) = layer_removed {
+                mind_model.animation_layers.remove(i);
+                changed = true;
+            }
+
+            if ui.button_with_size("Add Layer", [ui.content_region_avail()[0], 0.0f32]) {
+                mind_model
+                    .animation_layers
+                    .push(AnimationLayer::new(mind_model.animation_selected));
+                changed = true;
+            }
+
+            if changed {
+                mind_model.pose_revision += 1;
+            }
+        });
+}
+
+fn retarget(ui: &imgui::Ui, mind_model: &mut MindModel) {
+    ui.tree_node_config("Retarget")
+        .flags(imgui::TreeNodeFlags::SPAN_AVAIL_WIDTH)
+        .framed(true)
+        .build(|| {
+            let mut update = false;
+            let mut remove = false;
+
+            if let Some(retarget) = &mut mind_model.retarget {
+                ui.text(format!("Source Skeleton:   {}", retarget.skeleton_path));
+                ui.text(format!("Source Animations: {}", retarget.animations_path));
+                ui.text(format!(
+                    "Mapped Joints:     {} / {}",
+                    retarget.mapped_count(),
+                    mind_model.skeleton.joints.len()
+                ));
+
+                if ui.button("Map By Hash") {
+                    retarget.auto_map(&mind_model.skeleton, MatchBy::Hash);
+                }
+                ui.same_line();
+                if ui.button("Map By Name") {
+                    retarget.auto_map(&mind_model.skeleton, MatchBy::Name);
+                }
+                ui.same_line();
+                if ui.button("Clear") {
+                    retarget.clear();
+                }
+
+                let mut source_names = vec!["None"];
+                source_names.extend(
+                    retarget
+                        .skeleton
+                        .joints
+                        .iter()
+                        .map(|joint| joint.name.as_str()),
+                );
+
+                ui.child_window("##mapping")
+                    .size([0.0f32, 200.0f32])
+                    .border(true)
+                    .build(|| {
+                        for (i, joint) in mind_model.skeleton.joints.iter().enumerate() {
+                            let _mapping_id = ui.push_id_usize(i);
+
+                            let mut source_selected = retarget.mapping[i].map_or(0, |x| x + 1);
+                            ui.set_next_item_width(150.0f32);
+                            if ui.combo_simple_string(
+                                "##source_joint",
+                                &mut source_selected,
+                                &source_names,
+                            ) {
+                                retarget.mapping[i] = source_selected.checked_sub(1);
+                            }
+                            ui.same_line();
+                            ui.text(&joint.name);
+                        }
+                    });
+
+                if ui.button("Retarget Animations") {
+                    update = true;
+                }
+                ui.same_line();
+                if ui.button("Remove") {
+                    remove = true;
+                }
+            } else if ui.button_with_size(
+                "Load Source Skeleton",
+                [ui.content_region_avail()[0], 0.0f32],
+            ) {
+                let skeleton_path = FileDialog::new()
+                    .add_filter("Skeleton", &["skl"])
+                    .show_open_single_file()
+                    .unwrap();
+                if let Some(skeleton_path) = skeleton_path {
+                    let animations_path = FileDialog::new().show_open_single_dir().unwrap();
+                    if let Some(animations_path) = animations_path {
+                        crate::set_retarget(
+                            mind_model,
+                            skeleton_path.to_str().unwrap(),
+                            animations_path.to_str().unwrap(),
+                        );
+                    }
+                }
+            }
+
+            if update {
+                crate::update_retarget(mind_model);
+            }
+            if remove {
+                crate::remove_retarget(mind_model);
             }
         });
 }
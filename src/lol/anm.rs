@@ -1,13 +1,20 @@
-use byteorder::{LittleEndian, ReadBytesExt};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use std::{
-    collections::BTreeMap,
-    f32,
+    collections::{BTreeMap, HashMap},
+    f32, fs,
     io::{Cursor, Read},
+    path::Path,
 };
 
 use crate::{
     gls::glam_read,
-    lol::{hasher, Skeleton},
+    lol::{
+        hasher,
+        ik::{self, InverseKinematics},
+        layer::{self, AnimationLayer},
+        posing::Posing,
+        root_motion, Skeleton,
+    },
 };
 
 enum FrameDataType {
@@ -33,13 +40,16 @@ struct FrameIndices {
     pub scale_index: u16,
 }
 
+#[derive(Clone)]
 pub struct JointAnm {
     pub hash: u32,
+    pub name: Option<String>,
     pub translations: Vec<(f32, glam::Vec3)>,
     pub rotations: Vec<(f32, glam::Quat)>,
     pub scales: Vec<(f32, glam::Vec3)>,
 }
 
+#[derive(Clone)]
 pub struct Animation {
     pub fps: f32,
     pub duration: f32,
@@ -75,6 +85,387 @@ impl Animation {
         }
     }
 
+    pub fn snap_time(&self, time: f32) -> f32 {
+        if self.frame_delay > 0.0f32 {
+            ((time / self.frame_delay).round() * self.frame_delay).min(self.duration)
+        } else {
+            time
+        }
+    }
+
+    pub fn trim(&self, start: f32, end: f32) -> Animation {
+        let start = start.clamp(0.0f32, self.duration);
+        let end = end.clamp(start, self.duration);
+
+        fn trim_keys<T: Copy>(
+            keys: &[(f32, T)],
+            start: f32,
+            end: f32,
+            sample: impl Fn(f32) -> T,
+        ) -> Vec<(f32, T)> {
+            if keys.is_empty() {
+                return vec![];
+            }
+            let mut trimmed = vec![(0.0f32, sample(start))];
+            trimmed.extend(
+                keys.iter()
+                    .filter(|(time, _)| *time > start && *time < end)
+                    .map(|(time, value)| (*time - start, *value)),
+            );
+            trimmed.push((end - start, sample(end)));
+            trimmed
+        }
+
+        Animation {
+            fps: self.fps,
+            duration: end - start,
+            frame_delay: self.frame_delay,
+            joints: self
+                .joints
+                .iter()
+                .map(|joint| JointAnm {
+                    hash: joint.hash,
+                    name: joint.name.to_owned(),
+                    translations: trim_keys(&joint.translations, start, end, |time| {
+                        sample_joint(joint, time).translation
+                    }),
+                    rotations: trim_keys(&joint.rotations, start, end, |time| {
+                        sample_joint(joint, time).rotation
+                    }),
+                    scales: trim_keys(&joint.scales, start, end, |time| {
+                        sample_joint(joint, time).scale
+                    }),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn resample(&self, fps: f32) -> Animation {
+        let frame_delay = 1.0f32 / fps;
+        let frame_count = (self.duration * fps).round().max(1.0f32) as usize;
+        let times: Vec<f32> = (0..=frame_count)
+            .map(|frame| (frame as f32 * frame_delay).min(self.duration))
+            .collect();
+
+        Animation {
+            fps,
+            duration: self.duration,
+            frame_delay,
+            joints: self
+                .joints
+                .iter()
+                .map(|joint| {
+                    let poses: Vec<(f32, JointPose)> = times
+                        .iter()
+                        .map(|time| (*time, sample_joint(joint, *time)))
+                        .collect();
+                    JointAnm {
+                        hash: joint.hash,
+                        name: joint.name.to_owned(),
+                        translations: poses
+                            .iter()
+                            .map(|(time, pose)| (*time, pose.translation))
+                            .collect(),
+                        rotations: poses
+                            .iter()
+                            .map(|(time, pose)| (*time, pose.rotation))
+                            .collect(),
+                        scales: poses
+                            .iter()
+                            .map(|(time, pose)| (*time, pose.scale))
+                            .collect(),
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    pub fn time_scale(&self, scale: f32) -> Animation {
+        fn scale_keys<T: Copy>(keys: &[(f32, T)], scale: f32) -> Vec<(f32, T)> {
+            keys.iter()
+                .map(|(time, value)| (*time / scale, *value))
+                .collect()
+        }
+
+        Animation {
+            fps: self.fps,
+            duration: self.duration / scale,
+            frame_delay: self.frame_delay,
+            joints: self
+                .joints
+                .iter()
+                .map(|joint| JointAnm {
+                    hash: joint.hash,
+                    name: joint.name.to_owned(),
+                    translations: scale_keys(&joint.translations, scale),
+                    rotations: scale_keys(&joint.rotations, scale),
+                    scales: scale_keys(&joint.scales, scale),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn reverse(&self) -> Animation {
+        fn reverse_keys<T: Copy>(keys: &[(f32, T)]) -> Vec<(f32, T)> {
+            let last_time = keys.last().map_or(0.0f32, |(time, _)| *time);
+            keys.iter()
+                .rev()
+                .map(|(time, value)| ((last_time - *time).max(0.0f32), *value))
+                .collect()
+        }
+
+        Animation {
+            fps: self.fps,
+            duration: self.duration,
+            frame_delay: self.frame_delay,
+            joints: self
+                .joints
+                .iter()
+                .map(|joint| JointAnm {
+                    hash: joint.hash,
+                    name: joint.name.to_owned(),
+                    translations: reverse_keys(&joint.translations),
+                    rotations: reverse_keys(&joint.rotations),
+                    scales: reverse_keys(&joint.scales),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn concatenate(&self, other: &Animation) -> Animation {
+        fn concatenate_keys<T: Copy>(
+            first: Option<&[(f32, T)]>,
+            second: Option<&[(f32, T)]>,
+            first_duration: f32,
+            second_duration: f32,
+        ) -> Vec<(f32, T)> {
+            let first = first.filter(|keys| !keys.is_empty());
+            let second = second.filter(|keys| !keys.is_empty());
+
+            let mut keys: Vec<(f32, T)> = match (first, second) {
+                (Some(first), _) => first.to_vec(),
+                (None, Some(second)) => vec![(0.0f32, second[0].1)],
+                (None, None) => return vec![],
+            };
+            match (first, second) {
+                (_, Some(second)) => keys.extend(
+                    second
+                        .iter()
+                        .map(|(time, value)| (first_duration + *time, *value)),
+                ),
+                (Some(first), None) => {
+                    keys.push((first_duration + second_duration, first[first.len() - 1].1))
+                }
+                (None, None) => {}
+            }
+            keys
+        }
+
+        let mut hashes: Vec<u32> = self.joints.iter().map(|joint| joint.hash).collect();
+        for joint in other.joints.iter() {
+            if !hashes.contains(&joint.hash) {
+                hashes.push(joint.hash);
+            }
+        }
+
+        Animation {
+            fps: self.fps,
+            duration: self.duration + other.duration,
+            frame_delay: self.frame_delay,
+            joints: hashes
+                .iter()
+                .map(|hash| {
+                    let first = self.joints.iter().find(|joint| joint.hash == *hash);
+                    let second = other.joints.iter().find(|joint| joint.hash == *hash);
+                    JointAnm {
+                        hash: *hash,
+                        name: first
+                            .and_then(|joint| joint.name.to_owned())
+                            .or_else(|| second.and_then(|joint| joint.name.to_owned())),
+                        translations: concatenate_keys(
+                            first.map(|joint| joint.translations.as_slice()),
+                            second.map(|joint| joint.translations.as_slice()),
+                            self.duration,
+                            other.duration,
+                        ),
+                        rotations: concatenate_keys(
+                            first.map(|joint| joint.rotations.as_slice()),
+                            second.map(|joint| joint.rotations.as_slice()),
+                            self.duration,
+                            other.duration,
+                        ),
+                        scales: concatenate_keys(
+                            first.map(|joint| joint.scales.as_slice()),
+                            second.map(|joint| joint.scales.as_slice()),
+                            self.duration,
+                            other.duration,
+                        ),
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    pub fn write(&self, path: &Path) {
+        println!("Writing animation: {}", path.to_str().unwrap());
+
+        let contents = match self.write_v4() {
+            Ok(contents) => contents,
+            Err(error) => {
+                return println!("Could not write ANM: {error}");
+            }
+        };
+
+        if let Err(error) = fs::write(path, contents) {
+            println!("Could not write ANM file: {error}");
+        }
+    }
+
+    fn write_v4(&self) -> Result<Vec<u8>, String> {
+        // Frames have to reach the last key, which lands on the duration for edited clips
+        let last_time = self
+            .joints
+            .iter()
+            .flat_map(|joint| {
+                [
+                    joint.translations.last().map(|(time, _)| *time),
+                    joint.rotations.last().map(|(time, _)| *time),
+                    joint.scales.last().map(|(time, _)| *time),
+                ]
+            })
+            .flatten()
+            .fold(0.0f32, f32::max)
+            .min(self.duration);
+        let frame_count = if self.frame_delay > 0.0f32 {
+            (last_time / self.frame_delay - 0.0001f32)
+                .ceil()
+                .max(0.0f32) as u32
+                + 1
+        } else {
+            1
+        };
+
+        let mut vectors: Vec<glam::Vec3> = vec![];
+        let mut vector_indices: HashMap<[u32; 3], u16> = HashMap::new();
+        let mut rotations: Vec<glam::Quat> = vec![];
+        let mut rotation_indices: HashMap<[u32; 4], u16> = HashMap::new();
+
+        fn palette_index<const N: usize, T: Copy>(
+            palette: &mut Vec<T>,
+            indices: &mut HashMap<[u32; N], u16>,
+            key: [f32; N],
+            value: T,
+        ) -> Result<u16, String> {
+            let key = key.map(f32::to_bits);
+            if let Some(index) = indices.get(&key) {
+                return Ok(*index);
+            }
+            let index = u16::try_from(palette.len())
+                .map_err(|_| String::from("too many unique keyframe values for ANM v4"))?;
+            palette.push(value);
+            indices.insert(key, index);
+            Ok(index)
+        }
+
+        let mut frames: Vec<(u32, u16, u16, u16)> =
+            Vec::with_capacity(self.joints.len() * frame_count as usize);
+        for joint in self.joints.iter() {
+            for frame in 0..frame_count {
+                let pose = sample_joint(joint, frame as f32 * self.frame_delay);
+                let rotation = pose.rotation.normalize();
+                frames.push((
+                    joint.hash,
+                    palette_index(
+                        &mut vectors,
+                        &mut vector_indices,
+                        pose.translation.to_array(),
+                        pose.translation,
+                    )?,
+                    palette_index(
+                        &mut vectors,
+                        &mut vector_indices,
+                        pose.scale.to_array(),
+                        pose.scale,
+                    )?,
+                    palette_index(
+                        &mut rotations,
+                        &mut rotation_indices,
+                        rotation.to_array(),
+                        rotation,
+                    )?,
+                ));
+            }
+        }
+
+        let header_size = 64u32;
+        let vectors_offset = header_size;
+        let rotations_offset = vectors_offset + vectors.len() as u32 * 12;
+        let frame_offset = rotations_offset + rotations.len() as u32 * 16;
+        let file_size = 12 + frame_offset + frames.len() as u32 * 12;
+
+        let mut writer: Vec<u8> = Vec::with_capacity(file_size as usize);
+        let io_error = |error: std::io::Error| error.to_string();
+
+        writer.extend_from_slice(b"r3d2anmd");
+        writer.write_u32::<LittleEndian>(4).map_err(io_error)?;
+        writer
+            .write_u32::<LittleEndian>(file_size)
+            .map_err(io_error)?;
+        writer
+            .write_u32::<LittleEndian>(0xBE0794D3)
+            .map_err(io_error)?;
+        writer.write_u32::<LittleEndian>(0).map_err(io_error)?;
+        writer.write_u32::<LittleEndian>(0).map_err(io_error)?;
+        writer
+            .write_u32::<LittleEndian>(self.joints.len() as u32)
+            .map_err(io_error)?;
+        writer
+            .write_u32::<LittleEndian>(frame_count)
+            .map_err(io_error)?;
+        writer
+            .write_f32::<LittleEndian>(self.frame_delay)
+            .map_err(io_error)?;
+        writer.write_i32::<LittleEndian>(-1).map_err(io_error)?;
+        writer.write_i32::<LittleEndian>(-1).map_err(io_error)?;
+        writer.write_i32::<LittleEndian>(-1).map_err(io_error)?;
+        writer
+            .write_u32::<LittleEndian>(vectors_offset)
+            .map_err(io_error)?;
+        writer
+            .write_u32::<LittleEndian>(rotations_offset)
+            .map_err(io_error)?;
+        writer
+            .write_u32::<LittleEndian>(frame_offset)
+            .map_err(io_error)?;
+        writer.resize((12 + header_size) as usize, 0);
+
+        for vector in vectors.iter() {
+            for value in vector.to_array() {
+                writer.write_f32::<LittleEndian>(value).map_err(io_error)?;
+            }
+        }
+        for rotation in rotations.iter() {
+            for value in rotation.to_array() {
+                writer.write_f32::<LittleEndian>(value).map_err(io_error)?;
+            }
+        }
+        for (hash, translation_index, scale_index, rotation_index) in frames {
+            writer.write_u32::<LittleEndian>(hash).map_err(io_error)?;
+            writer
+                .write_u16::<LittleEndian>(translation_index)
+                .map_err(io_error)?;
+            writer
+                .write_u16::<LittleEndian>(scale_index)
+                .map_err(io_error)?;
+            writer
+                .write_u16::<LittleEndian>(rotation_index)
+                .map_err(io_error)?;
+            writer.write_u16::<LittleEndian>(0).map_err(io_error)?;
+        }
+
+        Ok(writer)
+    }
+
     fn read_compressed(reader: &mut Cursor<&Vec<u8>>) -> Animation {
         let version = reader
             .read_u32::<LittleEndian>()
@@ -172,6 +563,7 @@ impl Animation {
         for i in 0..joint_count {
             let mut joint_anm = JointAnm {
                 hash: hash_entries[i as usize],
+                name: None,
                 translations: vec![],
                 rotations: vec![],
                 scales: vec![],
@@ -303,6 +695,7 @@ impl Animation {
         for i in 0..joint_count {
             joints.push(JointAnm {
                 hash: hashes[i as usize],
+                name: None,
                 translations: vec![],
                 rotations: vec![],
                 scales: vec![],
@@ -429,6 +822,7 @@ impl Animation {
 
             let mut joint_anm = JointAnm {
                 hash,
+                name: None,
                 translations: Vec::with_capacity(frame_indices.len()),
                 rotations: Vec::with_capacity(frame_indices.len()),
                 scales: Vec::with_capacity(frame_indices.len()),
@@ -500,6 +894,7 @@ impl Animation {
 
             let mut joint_anm = JointAnm {
                 hash,
+                name: Some(name),
                 translations: Vec::with_capacity(frame_count as usize),
                 rotations: Vec::with_capacity(frame_count as usize),
                 scales: Vec::with_capacity(frame_count as usize),
@@ -601,52 +996,404 @@ pub fn find_in_nearest_time<T: Copy + Default>(vector: &Vec<(f32, T)>, time: f32
     }
 }
 
+#[derive(Clone, Copy)]
+pub struct JointPose {
+    pub translation: glam::Vec3,
+    pub rotation: glam::Quat,
+    pub scale: glam::Vec3,
+}
+
+impl JointPose {
+    pub const IDENTITY: JointPose = JointPose {
+        translation: glam::Vec3::ZERO,
+        rotation: glam::Quat::IDENTITY,
+        scale: glam::Vec3::ONE,
+    };
+
+    pub fn from_matrix(matrix: &glam::Mat4) -> JointPose {
+        let (scale, rotation, translation) = matrix.to_scale_rotation_translation();
+        JointPose {
+            translation,
+            rotation,
+            scale,
+        }
+    }
+
+    pub fn to_matrix(self) -> glam::Mat4 {
+        glam::Mat4::from_scale_rotation_translation(self.scale, self.rotation, self.translation)
+    }
+}
+
+pub fn sample_joint(joint: &JointAnm, time: f32) -> JointPose {
+    let (translation_min, translation_max, translation_lerp_value) =
+        find_in_nearest_time(&joint.translations, time);
+    let (rotation_min, rotation_max, rotation_lerp_value) =
+        find_in_nearest_time(&joint.rotations, time);
+    let (scale_min, scale_max, scale_lerp_value) = find_in_nearest_time(&joint.scales, time);
+
+    JointPose {
+        translation: translation_min.lerp(translation_max, translation_lerp_value),
+        rotation: rotation_min.lerp(rotation_max, rotation_lerp_value),
+        scale: scale_min.lerp(scale_max, scale_lerp_value),
+    }
+}
+
+pub fn sample_pose(animation: &Animation, skeleton: &Skeleton, time: f32) -> Vec<JointPose> {
+    skeleton
+        .joints
+        .iter()
+        .map(|skeleton_joint| {
+            let animation_joint = animation
+                .joints
+                .iter()
+                .find(|&joint| joint.hash == skeleton_joint.hash);
+
+            match animation_joint {
+                Some(joint) => sample_joint(joint, time),
+                None => JointPose::from_matrix(&skeleton_joint.local_matrix),
+            }
+        })
+        .collect()
+}
+
+pub fn pose_globals(pose: &[JointPose], skeleton: &Skeleton) -> Vec<glam::Mat4> {
+    let mut global_transforms: Vec<glam::Mat4> = skeleton
+        .joints
+        .iter()
+        .map(|joint| joint.local_matrix)
+        .collect();
+    for i in 0..skeleton.joints.len() {
+        let skeleton_joint = &skeleton.joints[i];
+
+        let mut global_transform = if skeleton_joint.parent_id != -1 {
+            global_transforms[skeleton_joint.parent_id as usize]
+        } else {
+            glam::Mat4::IDENTITY
+        };
+
+        global_transform *= pose[i].to_matrix();
+
+        global_transforms[i] = global_transform;
+    }
+    global_transforms
+}
+
+pub fn pose_from_transforms(
+    joint_transforms: &[glam::Mat4],
+    skeleton: &Skeleton,
+) -> Vec<JointPose> {
+    let global_transforms: Vec<glam::Mat4> = skeleton
+        .joints
+        .iter()
+        .enumerate()
+        .map(|(i, joint)| joint_transforms[i] * joint.global_matrix)
+        .collect();
+
+    skeleton
+        .joints
+        .iter()
+        .enumerate()
+        .map(|(i, joint)| {
+            let local_transform = if joint.parent_id != -1 {
+                global_transforms[joint.parent_id as usize].inverse() * global_transforms[i]
+            } else {
+                global_transforms[i]
+            };
+            JointPose::from_matrix(&local_transform)
+        })
+        .collect()
+}
+
+pub fn apply_pose(joint_transforms: &mut [glam::Mat4], pose: &[JointPose], skeleton: &Skeleton) {
+    let global_transforms = pose_globals(pose, skeleton);
+    for i in 0..skeleton.joints.len() {
+        joint_transforms[i] = global_transforms[i] * skeleton.joints[i].inverse_global_matrix;
+    }
+}
+
 pub fn run_animation(
     joint_transforms: &mut [glam::Mat4],
-    animation: &Animation,
+    animations: &[Animation],
+    animation_selected: usize,
+    layers: &[AnimationLayer],
     skeleton: &Skeleton,
     time: f32,
+    root_lock: Option<usize>,
+    posing: Option<&Posing>,
+    ik: Option<&InverseKinematics>,
 ) {
+    let animation = &animations[animation_selected];
     if time <= animation.duration {
-        let mut parent_transforms: Vec<glam::Mat4> = skeleton
-            .joints
-            .iter()
-            .map(|joint| joint.local_matrix)
+        let mut pose = sample_pose(animation, skeleton, time);
+        layer::apply_layers(&mut pose, layers, animations, skeleton, time);
+        if let Some(root) = root_lock {
+            root_motion::lock_root(&mut pose, animation, skeleton, root);
+        }
+        if let Some(posing) = posing {
+            posing.apply(&mut pose);
+        }
+        if let Some(ik) = ik {
+            ik::apply_ik(&mut pose, skeleton, ik);
+        }
+        apply_pose(joint_transforms, &pose, skeleton);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME_DELAY: f32 = 0.1f32;
+
+    fn test_animation(frame_count: usize) -> Animation {
+        let keys: Vec<f32> = (0..frame_count)
+            .map(|frame| frame as f32 * FRAME_DELAY)
             .collect();
-        for i in 0..skeleton.joints.len() {
-            let skeleton_joint = &skeleton.joints[i];
 
-            let mut global_transform = if skeleton_joint.parent_id != -1 {
-                parent_transforms[skeleton_joint.parent_id as usize]
-            } else {
-                glam::Mat4::IDENTITY
-            };
+        Animation {
+            fps: 1.0f32 / FRAME_DELAY,
+            duration: frame_count as f32 * FRAME_DELAY,
+            frame_delay: FRAME_DELAY,
+            joints: vec![JointAnm {
+                hash: hasher::string_to_hash("Root"),
+                name: None,
+                translations: keys
+                    .iter()
+                    .enumerate()
+                    .map(|(frame, time)| (*time, glam::vec3(frame as f32, 0.0f32, 0.0f32)))
+                    .collect(),
+                rotations: keys
+                    .iter()
+                    .enumerate()
+                    .map(|(frame, time)| {
+                        (*time, glam::Quat::from_rotation_y(frame as f32 * 0.1f32))
+                    })
+                    .collect(),
+                scales: keys.iter().map(|time| (*time, glam::Vec3::ONE)).collect(),
+            }],
+        }
+    }
 
-            let animation_joint = animation
-                .joints
-                .iter()
-                .find(|&joint| joint.hash == skeleton_joint.hash);
+    fn translations_x(animation: &Animation) -> Vec<(f32, f32)> {
+        animation.joints[0]
+            .translations
+            .iter()
+            .map(|(time, translation)| (*time, translation.x))
+            .collect()
+    }
 
-            if let Some(joint) = animation_joint {
-                let (translation_min, translation_max, translation_lerp_value) =
-                    find_in_nearest_time(&joint.translations, time);
-                let (rotation_min, rotation_max, rotation_lerp_value) =
-                    find_in_nearest_time(&joint.rotations, time);
-                let (scale_min, scale_max, scale_lerp_value) =
-                    find_in_nearest_time(&joint.scales, time);
+    fn assert_keys_eq(actual: &[(f32, f32)], expected: &[(f32, f32)]) {
+        assert_eq!(actual.len(), expected.len(), "{actual:?} != {expected:?}");
+        for ((time, value), (expected_time, expected_value)) in actual.iter().zip(expected) {
+            assert!(
+                (time - expected_time).abs() < 0.0001f32
+                    && (value - expected_value).abs() < 0.0001f32,
+                "{actual:?} != {expected:?}"
+            );
+        }
+    }
 
-                let translation = translation_min.lerp(translation_max, translation_lerp_value);
-                let rotation = rotation_min.lerp(rotation_max, rotation_lerp_value);
-                let scale = scale_min.lerp(scale_max, scale_lerp_value);
+    #[test]
+    fn write_v4_round_trip() {
+        let animation = test_animation(5);
+        let read = Animation::read(&animation.write_v4().unwrap());
 
-                global_transform *=
-                    glam::Mat4::from_scale_rotation_translation(scale, rotation, translation);
-            } else {
-                global_transform *= skeleton_joint.local_matrix;
-            }
+        assert_eq!(read.frame_delay, animation.frame_delay);
+        assert!((read.duration - animation.duration).abs() < 0.0001f32);
+        assert_keys_eq(&translations_x(&read), &translations_x(&animation));
+        for ((_, rotation), (_, expected)) in read.joints[0]
+            .rotations
+            .iter()
+            .zip(animation.joints[0].rotations.iter())
+        {
+            assert!(rotation.abs_diff_eq(*expected, 0.0001f32));
+        }
+    }
+
+    #[test]
+    fn write_v4_keeps_frame_count() {
+        let animation = test_animation(5);
+        let read = Animation::read(&animation.write_v4().unwrap());
 
-            parent_transforms[i] = global_transform;
-            joint_transforms[i] = global_transform * skeleton_joint.inverse_global_matrix;
+        assert!((read.duration - animation.duration).abs() < 0.0001f32);
+        assert_eq!(read.joints[0].translations.len(), 5);
+    }
+
+    #[test]
+    fn reverse_mirrors_around_last_key() {
+        let reversed = test_animation(5).reverse();
+
+        assert_keys_eq(
+            &translations_x(&reversed),
+            &[(0.0, 4.0), (0.1, 3.0), (0.2, 2.0), (0.3, 1.0), (0.4, 0.0)],
+        );
+        assert_eq!(reversed.duration, test_animation(5).duration);
+    }
+
+    #[test]
+    fn reverse_trimmed_keeps_key_spacing() {
+        let reversed = test_animation(5).trim(0.15f32, 0.35f32).reverse();
+
+        assert_keys_eq(
+            &translations_x(&reversed),
+            &[(0.0, 3.5), (0.05, 3.0), (0.15, 2.0), (0.2, 1.5)],
+        );
+    }
+
+    #[test]
+    fn reverse_twice_is_identity() {
+        let animation = test_animation(5);
+        assert_keys_eq(
+            &translations_x(&animation.reverse().reverse()),
+            &translations_x(&animation),
+        );
+    }
+
+    #[test]
+    fn reverse_write_v4_round_trip() {
+        let reversed = test_animation(5).reverse();
+        let read = Animation::read(&reversed.write_v4().unwrap());
+
+        assert_keys_eq(&translations_x(&read), &translations_x(&reversed));
+        assert_round_trip(&reversed);
+    }
+
+    fn assert_round_trip(animation: &Animation) {
+        let read = Animation::read(&animation.write_v4().unwrap());
+
+        assert_eq!(read.frame_delay, animation.frame_delay);
+        for (time, x) in translations_x(&read) {
+            let expected = sample_joint(&animation.joints[0], time).translation.x;
+            assert!(
+                (x - expected).abs() < 0.0001f32,
+                "{time}: {x} != {expected}"
+            );
+        }
+
+        for (joint, expected_joint) in read.joints.iter().zip(animation.joints.iter()) {
+            let pose = sample_joint(joint, animation.duration);
+            let expected = sample_joint(expected_joint, animation.duration);
+            assert!(
+                pose.translation
+                    .abs_diff_eq(expected.translation, 0.0001f32)
+                    && pose.rotation.abs_diff_eq(expected.rotation, 0.0001f32),
+                "final pose {:?} != {:?}",
+                pose.translation,
+                expected.translation
+            );
+        }
+    }
+
+    #[test]
+    fn trim_samples_boundary_keys() {
+        let trimmed = test_animation(5).trim(0.15f32, 0.35f32);
+
+        assert!((trimmed.duration - 0.2f32).abs() < 0.0001f32);
+        assert_keys_eq(
+            &translations_x(&trimmed),
+            &[(0.0, 1.5), (0.05, 2.0), (0.15, 3.0), (0.2, 3.5)],
+        );
+    }
+
+    #[test]
+    fn trim_clamps_to_duration() {
+        let animation = test_animation(5);
+        let trimmed = animation.trim(-1.0f32, 10.0f32);
+
+        assert_eq!(trimmed.duration, animation.duration);
+        assert_keys_eq(
+            &translations_x(&trimmed),
+            &[
+                (0.0, 0.0),
+                (0.1, 1.0),
+                (0.2, 2.0),
+                (0.3, 3.0),
+                (0.4, 4.0),
+                (0.5, 4.0),
+            ],
+        );
+    }
+
+    #[test]
+    fn trim_write_v4_round_trip() {
+        assert_round_trip(&test_animation(5).trim(0.1f32, 0.4f32));
+    }
+
+    #[test]
+    fn resample_keeps_boundary_keys() {
+        let animation = test_animation(5);
+        let resampled = animation.resample(20.0f32);
+
+        assert_eq!(resampled.duration, animation.duration);
+        assert_eq!(resampled.frame_delay, 0.05f32);
+
+        let keys = translations_x(&resampled);
+        assert_eq!(keys.len(), 11);
+        assert_eq!(keys[0], (0.0f32, 0.0f32));
+        assert_eq!(keys[keys.len() - 1].0, animation.duration);
+        for (time, x) in keys {
+            assert!((x - (time * 10.0f32).min(4.0f32)).abs() < 0.0001f32);
         }
     }
+
+    #[test]
+    fn resample_write_v4_round_trip() {
+        assert_round_trip(&test_animation(5).resample(20.0f32));
+    }
+
+    #[test]
+    fn time_scale_scales_keys() {
+        let scaled = test_animation(5).time_scale(2.0f32);
+
+        assert!((scaled.duration - 0.25f32).abs() < 0.0001f32);
+        assert_keys_eq(
+            &translations_x(&scaled),
+            &[(0.0, 0.0), (0.05, 1.0), (0.1, 2.0), (0.15, 3.0), (0.2, 4.0)],
+        );
+    }
+
+    #[test]
+    fn time_scale_inverse_is_identity() {
+        let animation = test_animation(5);
+        let scaled = animation.time_scale(2.0f32).time_scale(0.5f32);
+
+        assert!((scaled.duration - animation.duration).abs() < 0.0001f32);
+        assert_keys_eq(&translations_x(&scaled), &translations_x(&animation));
+    }
+
+    #[test]
+    fn concatenate_offsets_second_keys() {
+        let concatenated = test_animation(2).concatenate(&test_animation(3));
+
+        assert!((concatenated.duration - 0.5f32).abs() < 0.0001f32);
+        assert_keys_eq(
+            &translations_x(&concatenated),
+            &[(0.0, 0.0), (0.1, 1.0), (0.2, 0.0), (0.3, 1.0), (0.4, 2.0)],
+        );
+    }
+
+    #[test]
+    fn concatenate_holds_unshared_joints() {
+        let mut second = test_animation(3);
+        second.joints[0].hash = hasher::string_to_hash("Other");
+        let concatenated = test_animation(2).concatenate(&second);
+
+        assert_eq!(concatenated.joints.len(), 2);
+        assert_keys_eq(
+            &translations_x(&concatenated),
+            &[(0.0, 0.0), (0.1, 1.0), (0.5, 1.0)],
+        );
+
+        let other: Vec<(f32, f32)> = concatenated.joints[1]
+            .translations
+            .iter()
+            .map(|(time, translation)| (*time, translation.x))
+            .collect();
+        assert_keys_eq(&other, &[(0.0, 0.0), (0.2, 0.0), (0.3, 1.0), (0.4, 2.0)]);
+    }
+
+    #[test]
+    fn concatenate_write_v4_round_trip() {
+        assert_round_trip(&test_animation(2).concatenate(&test_animation(3)));
+    }
 }
@@ -0,0 +1,126 @@
+use crate::lol::{
+    anm::{pose_globals, sample_pose},
+    Animation, Skeleton,
+};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum CompareMetric {
+    Position,
+    Angle,
+}
+
+pub struct JointDifference {
+    pub joint: usize,
+    pub max_position: f32,
+    pub max_angle: f32,
+    pub mean_position: f32,
+    pub mean_angle: f32,
+}
+
+pub struct Comparison {
+    pub name: String,
+    pub animation: Animation,
+    pub compared_animation: Option<usize>,
+    pub frame_delay: f32,
+    pub position_differences: Vec<Vec<f32>>,
+    pub angle_differences: Vec<Vec<f32>>,
+    pub joints: Vec<JointDifference>,
+    pub metric: CompareMetric,
+    pub show_ghost: bool,
+    pub ghost_color: [f32; 4],
+    pub joints_transforms: Vec<glam::Mat4>,
+}
+
+impl Comparison {
+    pub fn new(name: String, animation: Animation, skeleton: &Skeleton) -> Comparison {
+        Comparison {
+            name,
+            animation,
+            compared_animation: None,
+            frame_delay: 0.0f32,
+            position_differences: vec![],
+            angle_differences: vec![],
+            joints: vec![],
+            metric: CompareMetric::Position,
+            show_ghost: true,
+            ghost_color: [0.2f32, 0.6f32, 1.0f32, 0.4f32],
+            joints_transforms: vec![glam::Mat4::IDENTITY; skeleton.joints.len()],
+        }
+    }
+
+    pub fn compare(&mut self, animation: &Animation, animation_index: usize, skeleton: &Skeleton) {
+        self.compared_animation = Some(animation_index);
+        self.frame_delay = animation.frame_delay.min(self.animation.frame_delay);
+
+        let duration = animation.duration.max(self.animation.duration);
+        let frame_count = if self.frame_delay > 0.0f32 {
+            (duration / self.frame_delay).round() as usize + 1
+        } else {
+            1
+        };
+
+        self.position_differences = vec![Vec::with_capacity(frame_count); skeleton.joints.len()];
+        self.angle_differences = vec![Vec::with_capacity(frame_count); skeleton.joints.len()];
+
+        for frame in 0..frame_count {
+            let time = frame as f32 * self.frame_delay;
+            let globals_a = pose_globals(
+                &sample_pose(animation, skeleton, time.min(animation.duration)),
+                skeleton,
+            );
+            let globals_b = pose_globals(
+                &sample_pose(&self.animation, skeleton, time.min(self.animation.duration)),
+                skeleton,
+            );
+
+            for joint in 0..skeleton.joints.len() {
+                let (_, rotation_a, translation_a) =
+                    globals_a[joint].to_scale_rotation_translation();
+                let (_, rotation_b, translation_b) =
+                    globals_b[joint].to_scale_rotation_translation();
+
+                self.position_differences[joint].push(translation_a.distance(translation_b));
+                self.angle_differences[joint]
+                    .push(rotation_a.angle_between(rotation_b).to_degrees());
+            }
+        }
+
+        self.joints = (0..skeleton.joints.len())
+            .map(|joint| {
+                let positions = &self.position_differences[joint];
+                let angles = &self.angle_differences[joint];
+                JointDifference {
+                    joint,
+                    max_position: positions.iter().copied().fold(0.0f32, f32::max),
+                    max_angle: angles.iter().copied().fold(0.0f32, f32::max),
+                    mean_position: positions.iter().sum::<f32>() / frame_count as f32,
+                    mean_angle: angles.iter().sum::<f32>() / frame_count as f32,
+                }
+            })
+            .collect();
+        self.sort();
+    }
+
+    pub fn sort(&mut self) {
+        match self.metric {
+            CompareMetric::Position => self
+                .joints
+                .sort_by(|a, b| b.max_position.total_cmp(&a.max_position)),
+            CompareMetric::Angle => self
+                .joints
+                .sort_by(|a, b| b.max_angle.total_cmp(&a.max_angle)),
+        }
+    }
+
+    pub fn frame(&self, time: f32) -> usize {
+        let frame_count = self
+            .position_differences
+            .first()
+            .map_or(0, |frames| frames.len());
+        if self.frame_delay > 0.0f32 {
+            ((time / self.frame_delay).round() as usize).min(frame_count.saturating_sub(1))
+        } else {
+            0
+        }
+    }
+}
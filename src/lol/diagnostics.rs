@@ -0,0 +1,177 @@
+use serde::Serialize;
+use std::{fs, path::Path};
+
+use crate::lol::{anm::JointAnm, Animation, Skeleton};
+
+const CONSTANT_EPSILON: f32 = 0.0001f32;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TrackReport {
+    #[serde(rename = "Hash")]
+    pub hash: u32,
+
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+
+    #[serde(rename = "TranslationKeys")]
+    pub translation_keys: usize,
+
+    #[serde(rename = "RotationKeys")]
+    pub rotation_keys: usize,
+
+    #[serde(rename = "ScaleKeys")]
+    pub scale_keys: usize,
+
+    #[serde(rename = "Constant")]
+    pub constant: bool,
+
+    #[serde(rename = "MatchesSkeleton")]
+    pub matches_skeleton: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AnimationReport {
+    #[serde(rename = "Animation")]
+    pub animation_name: String,
+
+    #[serde(skip)]
+    pub animation: usize,
+
+    #[serde(rename = "Fps")]
+    pub fps: f32,
+
+    #[serde(rename = "Duration")]
+    pub duration: f32,
+
+    #[serde(rename = "FrameCount")]
+    pub frame_count: usize,
+
+    #[serde(rename = "MissingTracks")]
+    pub missing_tracks: Vec<String>,
+
+    #[serde(rename = "UnmatchedTracks")]
+    pub unmatched_tracks: Vec<TrackReport>,
+
+    #[serde(rename = "ConstantTracks")]
+    pub constant_tracks: Vec<TrackReport>,
+
+    #[serde(rename = "Tracks")]
+    pub tracks: Vec<TrackReport>,
+}
+
+impl AnimationReport {
+    pub fn new(
+        animation: &Animation,
+        animation_index: usize,
+        animation_name: &str,
+        skeleton: &Skeleton,
+        name_sources: &[&Skeleton],
+    ) -> AnimationReport {
+        let tracks: Vec<TrackReport> = animation
+            .joints
+            .iter()
+            .map(|joint| {
+                let matches_skeleton = skeleton
+                    .joints
+                    .iter()
+                    .any(|skeleton_joint| skeleton_joint.hash == joint.hash);
+                let name = joint.name.to_owned().or_else(|| {
+                    std::iter::once(skeleton)
+                        .chain(name_sources.iter().copied())
+                        .flat_map(|skeleton| skeleton.joints.iter())
+                        .find(|skeleton_joint| skeleton_joint.hash == joint.hash)
+                        .map(|skeleton_joint| skeleton_joint.name.to_owned())
+                });
+
+                TrackReport {
+                    hash: joint.hash,
+                    name,
+                    translation_keys: joint.translations.len(),
+                    rotation_keys: joint.rotations.len(),
+                    scale_keys: joint.scales.len(),
+                    constant: is_constant(joint),
+                    matches_skeleton,
+                }
+            })
+            .collect();
+
+        let missing_tracks = skeleton
+            .joints
+            .iter()
+            .filter(|skeleton_joint| {
+                !animation
+                    .joints
+                    .iter()
+                    .any(|joint| joint.hash == skeleton_joint.hash)
+            })
+            .map(|skeleton_joint| skeleton_joint.name.to_owned())
+            .collect();
+
+        AnimationReport {
+            animation_name: animation_name.to_owned(),
+            animation: animation_index,
+            fps: animation.fps,
+            duration: animation.duration,
+            frame_count: (animation.duration * animation.fps).round() as usize,
+            missing_tracks,
+            unmatched_tracks: tracks
+                .iter()
+                .filter(|track| !track.matches_skeleton)
+                .cloned()
+                .collect(),
+            constant_tracks: tracks
+                .iter()
+                .filter(|track| track.constant)
+                .cloned()
+                .collect(),
+            tracks,
+        }
+    }
+}
+
+impl TrackReport {
+    pub fn display_name(&self) -> String {
+        match &self.name {
+            Some(name) => format!("{} ({:#010x})", name, self.hash),
+            None => format!("{:#010x}", self.hash),
+        }
+    }
+}
+
+fn is_constant(joint: &JointAnm) -> bool {
+    let translations_constant = joint
+        .translations
+        .iter()
+        .all(|(_, translation)| translation.abs_diff_eq(joint.translations[0].1, CONSTANT_EPSILON));
+    let rotations_constant = joint
+        .rotations
+        .iter()
+        .all(|(_, rotation)| rotation.dot(joint.rotations[0].1).abs() >= 1.0f32 - CONSTANT_EPSILON);
+    let scales_constant = joint
+        .scales
+        .iter()
+        .all(|(_, scale)| scale.abs_diff_eq(joint.scales[0].1, CONSTANT_EPSILON));
+
+    translations_constant && rotations_constant && scales_constant
+}
+
+pub fn write_reports(path: &Path, reports: &[AnimationReport]) {
+    println!("Writing diagnostics report: {}", path.to_str().unwrap());
+
+    if let Some(parent) = path.parent() {
+        if let Err(error) = fs::create_dir_all(parent) {
+            return println!("Could not create diagnostics dirs: {error}");
+        }
+    }
+
+    let contents = match serde_json::to_string_pretty(reports) {
+        Ok(contents) => contents,
+        Err(error) => {
+            return println!("Could not serialize diagnostics report: {error}");
+        }
+    };
+
+    if let Err(error) = fs::write(path, contents) {
+        println!("Could not write diagnostics report: {error}");
+    }
+}
@@ -0,0 +1,255 @@
+use crate::lol::{
+    anm::{pose_globals, JointPose},
+    skl::joint_position,
+    Skeleton,
+};
+
+pub const AIM_AXES: [(&str, glam::Vec3); 6] = [
+    ("+X", glam::Vec3::X),
+    ("-X", glam::Vec3::NEG_X),
+    ("+Y", glam::Vec3::Y),
+    ("-Y", glam::Vec3::NEG_Y),
+    ("+Z", glam::Vec3::Z),
+    ("-Z", glam::Vec3::NEG_Z),
+];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum IkHandle {
+    Target,
+    Pole,
+    LookAt,
+}
+
+pub struct InverseKinematics {
+    pub two_bone: bool,
+    pub end_joint: Option<usize>,
+    pub target: glam::Vec3,
+    pub pole: glam::Vec3,
+    pub look_at: bool,
+    pub look_at_joint: Option<usize>,
+    pub look_at_target: glam::Vec3,
+    pub aim_axis: usize,
+    pub drag_handle: Option<IkHandle>,
+}
+
+impl InverseKinematics {
+    pub fn new() -> InverseKinematics {
+        InverseKinematics {
+            two_bone: false,
+            end_joint: None,
+            target: glam::Vec3::ZERO,
+            pole: glam::Vec3::ZERO,
+            look_at: false,
+            look_at_joint: None,
+            look_at_target: glam::Vec3::ZERO,
+            aim_axis: 4,
+            drag_handle: None,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        (self.two_bone && self.end_joint.is_some())
+            || (self.look_at && self.look_at_joint.is_some())
+    }
+
+    pub fn chain(&self, skeleton: &Skeleton) -> Option<[usize; 3]> {
+        let end = self.end_joint?;
+        let middle = parent(skeleton, end)?;
+        let root = parent(skeleton, middle)?;
+        Some([root, middle, end])
+    }
+
+    pub fn set_end_joint(
+        &mut self,
+        end_joint: usize,
+        joint_transforms: &[glam::Mat4],
+        skeleton: &Skeleton,
+    ) {
+        self.end_joint = Some(end_joint);
+        let Some([root, middle, end]) = self.chain(skeleton) else {
+            return;
+        };
+
+        let position = |joint: usize| {
+            joint_position(&(joint_transforms[joint] * skeleton.joints[joint].global_matrix))
+        };
+        let (root, middle, end) = (position(root), position(middle), position(end));
+
+        self.target = end;
+        let bend = bend_direction(root, middle, end, glam::Vec3::Y);
+        self.pole = middle + bend * root.distance(middle).max(1.0f32);
+    }
+
+    pub fn set_look_at_joint(
+        &mut self,
+        look_at_joint: usize,
+        joint_transforms: &[glam::Mat4],
+        skeleton: &Skeleton,
+    ) {
+        self.look_at_joint = Some(look_at_joint);
+        let global = joint_transforms[look_at_joint] * skeleton.joints[look_at_joint].global_matrix;
+        let length = skeleton.joints[look_at_joint]
+            .local_matrix
+            .w_axis
+            .truncate()
+            .length()
+            .max(1.0f32);
+        self.look_at_target = joint_position(&global)
+            + global
+                .transform_vector3(AIM_AXES[self.aim_axis].1)
+                .normalize_or_zero()
+                * length
+                * 4.0f32;
+    }
+}
+
+fn parent(skeleton: &Skeleton, joint: usize) -> Option<usize> {
+    let parent_id = skeleton.joints[joint].parent_id;
+    (parent_id != -1).then_some(parent_id as usize)
+}
+
+fn bend_direction(
+    root: glam::Vec3,
+    middle: glam::Vec3,
+    end: glam::Vec3,
+    fallback: glam::Vec3,
+) -> glam::Vec3 {
+    let direction = (end - root).normalize_or_zero();
+    let bend = middle - root;
+    let bend = (bend - direction * bend.dot(direction)).normalize_or_zero();
+    let fallback = (fallback - direction * fallback.dot(direction)).normalize_or_zero();
+    if bend != glam::Vec3::ZERO {
+        bend
+    } else if fallback != glam::Vec3::ZERO {
+        fallback
+    } else {
+        direction.any_orthonormal_vector()
+    }
+}
+
+fn rotate_global(pose: &mut [JointPose], skeleton: &Skeleton, joint: usize, rotation: glam::Quat) {
+    let globals = pose_globals(pose, skeleton);
+    let pivot = joint_position(&globals[joint]);
+    let global = glam::Mat4::from_translation(pivot)
+        * glam::Mat4::from_quat(rotation)
+        * glam::Mat4::from_translation(-pivot)
+        * globals[joint];
+    let local = match parent(skeleton, joint) {
+        Some(parent) => globals[parent].inverse() * global,
+        None => global,
+    };
+    pose[joint].rotation = JointPose::from_matrix(&local).rotation.normalize();
+}
+
+pub fn apply_ik(pose: &mut [JointPose], skeleton: &Skeleton, ik: &InverseKinematics) {
+    if ik.two_bone {
+        if let Some(chain) = ik.chain(skeleton) {
+            solve_two_bone(pose, skeleton, chain, ik.target, ik.pole);
+        }
+    }
+
+    if ik.look_at {
+        if let Some(joint) = ik.look_at_joint {
+            solve_look_at(
+                pose,
+                skeleton,
+                joint,
+                ik.look_at_target,
+                AIM_AXES[ik.aim_axis].1,
+            );
+        }
+    }
+}
+
+fn solve_two_bone(
+    pose: &mut [JointPose],
+    skeleton: &Skeleton,
+    [root, middle, end]: [usize; 3],
+    target: glam::Vec3,
+    pole: glam::Vec3,
+) {
+    let globals = pose_globals(pose, skeleton);
+    let root_position = joint_position(&globals[root]);
+    let middle_position = joint_position(&globals[middle]);
+    let end_position = joint_position(&globals[end]);
+
+    let upper_length = root_position.distance(middle_position);
+    let lower_length = middle_position.distance(end_position);
+    if upper_length <= f32::EPSILON || lower_length <= f32::EPSILON {
+        return;
+    }
+
+    let direction = (target - root_position).normalize_or_zero();
+    if direction == glam::Vec3::ZERO {
+        return;
+    }
+    let target_length = root_position.distance(target).clamp(
+        (upper_length - lower_length).abs() + 0.0001f32,
+        upper_length + lower_length - 0.0001f32,
+    );
+
+    let pole_direction = pole - root_position;
+    let pole_direction =
+        (pole_direction - direction * pole_direction.dot(direction)).normalize_or_zero();
+    let pole_direction = if pole_direction != glam::Vec3::ZERO {
+        pole_direction
+    } else {
+        bend_direction(root_position, middle_position, end_position, glam::Vec3::Y)
+    };
+
+    let cos_root = ((upper_length * upper_length + target_length * target_length
+        - lower_length * lower_length)
+        / (2.0f32 * upper_length * target_length))
+        .clamp(-1.0f32, 1.0f32);
+    let sin_root = (1.0f32 - cos_root * cos_root).sqrt();
+
+    let solved_middle = root_position
+        + direction * (upper_length * cos_root)
+        + pole_direction * (upper_length * sin_root);
+    let solved_end = root_position + direction * target_length;
+
+    rotate_global(
+        pose,
+        skeleton,
+        root,
+        glam::Quat::from_rotation_arc(
+            (middle_position - root_position).normalize(),
+            (solved_middle - root_position).normalize(),
+        ),
+    );
+
+    let globals = pose_globals(pose, skeleton);
+    let middle_position = joint_position(&globals[middle]);
+    let end_position = joint_position(&globals[end]);
+    rotate_global(
+        pose,
+        skeleton,
+        middle,
+        glam::Quat::from_rotation_arc(
+            (end_position - middle_position).normalize(),
+            (solved_end - middle_position).normalize(),
+        ),
+    );
+}
+
+fn solve_look_at(
+    pose: &mut [JointPose],
+    skeleton: &Skeleton,
+    joint: usize,
+    target: glam::Vec3,
+    aim_axis: glam::Vec3,
+) {
+    let global = pose_globals(pose, skeleton)[joint];
+    let aim = global.transform_vector3(aim_axis).normalize_or_zero();
+    let direction = (target - joint_position(&global)).normalize_or_zero();
+    if aim == glam::Vec3::ZERO || direction == glam::Vec3::ZERO {
+        return;
+    }
+
+    rotate_global(
+        pose,
+        skeleton,
+        joint,
+        glam::Quat::from_rotation_arc(aim, direction),
+    );
+}
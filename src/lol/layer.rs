@@ -0,0 +1,98 @@
+use crate::lol::{
+    anm::{self, JointPose},
+    Animation, Skeleton,
+};
+
+pub struct AnimationLayer {
+    pub animation: usize,
+    pub weight: f32,
+    pub additive: bool,
+    pub mask: Option<usize>,
+}
+
+impl AnimationLayer {
+    pub fn new(animation: usize) -> AnimationLayer {
+        AnimationLayer {
+            animation,
+            weight: 1.0f32,
+            additive: false,
+            mask: None,
+        }
+    }
+
+    fn joint_mask(&self, skeleton: &Skeleton) -> Vec<bool> {
+        match self.mask {
+            Some(mask) if mask < skeleton.joints.len() => {
+                let mut joint_mask = vec![false; skeleton.joints.len()];
+                for joint_index in skeleton.subtree(mask) {
+                    joint_mask[joint_index] = true;
+                }
+                joint_mask
+            }
+            _ => vec![true; skeleton.joints.len()],
+        }
+    }
+}
+
+pub fn apply_layers(
+    pose: &mut [JointPose],
+    layers: &[AnimationLayer],
+    animations: &[Animation],
+    skeleton: &Skeleton,
+    time: f32,
+) {
+    for layer in layers {
+        if layer.weight <= 0.0f32 || layer.animation >= animations.len() {
+            continue;
+        }
+
+        let animation = &animations[layer.animation];
+        let layer_time = if animation.duration > 0.0f32 {
+            time % animation.duration
+        } else {
+            0.0f32
+        };
+
+        let joint_mask = layer.joint_mask(skeleton);
+
+        for (i, skeleton_joint) in skeleton.joints.iter().enumerate() {
+            if !joint_mask[i] {
+                continue;
+            }
+
+            let animation_joint = animation
+                .joints
+                .iter()
+                .find(|&joint| joint.hash == skeleton_joint.hash);
+
+            if let Some(joint) = animation_joint {
+                let layer_pose = anm::sample_joint(joint, layer_time);
+
+                if layer.additive {
+                    // Additive layers are relative to the first frame of their own clip
+                    let reference_pose = anm::sample_joint(joint, 0.0f32);
+
+                    let translation = layer_pose.translation - reference_pose.translation;
+                    let rotation = reference_pose.rotation.inverse() * layer_pose.rotation;
+                    let scale = if reference_pose.scale.cmpne(glam::Vec3::ZERO).all() {
+                        layer_pose.scale / reference_pose.scale
+                    } else {
+                        glam::Vec3::ONE
+                    };
+
+                    pose[i].translation += translation * layer.weight;
+                    pose[i].rotation = (pose[i].rotation
+                        * glam::Quat::IDENTITY.slerp(rotation, layer.weight))
+                    .normalize();
+                    pose[i].scale *= glam::Vec3::ONE.lerp(scale, layer.weight);
+                } else {
+                    pose[i].translation = pose[i]
+                        .translation
+                        .lerp(layer_pose.translation, layer.weight);
+                    pose[i].rotation = pose[i].rotation.slerp(layer_pose.rotation, layer.weight);
+                    pose[i].scale = pose[i].scale.lerp(layer_pose.scale, layer.weight);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,352 @@
+use crate::lol::{
+    anm::{pose_globals, sample_pose, JointAnm, JointPose},
+    Animation, Skeleton,
+};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum MirrorAxis {
+    X,
+    Y,
+    Z,
+}
+
+pub struct MirrorPairs {
+    pub pairs: Vec<usize>,
+    pub unpaired: Vec<String>,
+}
+
+impl MirrorPairs {
+    pub fn new(skeleton: &Skeleton) -> MirrorPairs {
+        let mut pairs: Vec<usize> = (0..skeleton.joints.len()).collect();
+        let mut unpaired: Vec<String> = vec![];
+
+        for (i, joint) in skeleton.joints.iter().enumerate() {
+            let Some(mirror_name) = mirror_name(&joint.name) else {
+                continue;
+            };
+
+            match skeleton
+                .joints
+                .iter()
+                .position(|other| other.name.eq_ignore_ascii_case(&mirror_name))
+            {
+                Some(pair) => pairs[i] = pair,
+                None => unpaired.push(joint.name.to_owned()),
+            }
+        }
+
+        MirrorPairs { pairs, unpaired }
+    }
+
+    pub fn paired_count(&self) -> usize {
+        self.pairs
+            .iter()
+            .enumerate()
+            .filter(|(i, pair)| *i != **pair)
+            .count()
+    }
+}
+
+fn mirror_name(name: &str) -> Option<String> {
+    let prefix = name.get(..2)?;
+    let rest = &name[2..];
+    match prefix {
+        "L_" => Some(format!("R_{rest}")),
+        "R_" => Some(format!("L_{rest}")),
+        "l_" => Some(format!("r_{rest}")),
+        "r_" => Some(format!("l_{rest}")),
+        _ => None,
+    }
+}
+
+pub fn mirror_animation(
+    animation: &Animation,
+    skeleton: &Skeleton,
+    mirror_pairs: &MirrorPairs,
+    axis: MirrorAxis,
+) -> Animation {
+    let reflection = glam::Mat4::from_scale(match axis {
+        MirrorAxis::X => glam::vec3(-1.0f32, 1.0f32, 1.0f32),
+        MirrorAxis::Y => glam::vec3(1.0f32, -1.0f32, 1.0f32),
+        MirrorAxis::Z => glam::vec3(1.0f32, 1.0f32, -1.0f32),
+    });
+
+    let corrections: Vec<glam::Mat4> = skeleton
+        .joints
+        .iter()
+        .enumerate()
+        .map(|(i, joint)| {
+            let pair_global = skeleton.joints[mirror_pairs.pairs[i]].global_matrix;
+            (reflection * pair_global * reflection).inverse() * joint.global_matrix
+        })
+        .collect();
+
+    let tracked: Vec<bool> = skeleton
+        .joints
+        .iter()
+        .enumerate()
+        .map(|(i, _)| {
+            [i, mirror_pairs.pairs[i]].iter().any(|joint_index| {
+                let hash = skeleton.joints[*joint_index].hash;
+                animation.joints.iter().any(|joint| joint.hash == hash)
+            })
+        })
+        .collect();
+
+    let frame_count = if animation.frame_delay > 0.0f32 {
+        (animation.duration / animation.frame_delay).round() as usize + 1
+    } else {
+        1
+    };
+
+    let mut poses: Vec<Vec<(f32, JointPose)>> =
+        vec![Vec::with_capacity(frame_count); skeleton.joints.len()];
+    for frame in 0..frame_count {
+        let time = (frame as f32 * animation.frame_delay).min(animation.duration);
+        let globals = pose_globals(&sample_pose(animation, skeleton, time), skeleton);
+
+        let mirrored_globals: Vec<glam::Mat4> = (0..skeleton.joints.len())
+            .map(|i| reflection * globals[mirror_pairs.pairs[i]] * reflection * corrections[i])
+            .collect();
+
+        for (i, joint) in skeleton.joints.iter().enumerate() {
+            let local = if joint.parent_id != -1 {
+                mirrored_globals[joint.parent_id as usize].inverse() * mirrored_globals[i]
+            } else {
+                mirrored_globals[i]
+            };
+            poses[i].push((time, JointPose::from_matrix(&local)));
+        }
+    }
+
+    let mut joints: Vec<JointAnm> = skeleton
+        .joints
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| tracked[*i])
+        .map(|(i, joint)| JointAnm {
+            hash: joint.hash,
+            name: Some(joint.name.to_owned()),
+            translations: poses[i]
+                .iter()
+                .map(|(time, pose)| (*time, pose.translation))
+                .collect(),
+            rotations: poses[i]
+                .iter()
+                .map(|(time, pose)| (*time, pose.rotation))
+                .collect(),
+            scales: poses[i]
+                .iter()
+                .map(|(time, pose)| (*time, pose.scale))
+                .collect(),
+        })
+        .collect();
+
+    joints.extend(
+        animation
+            .joints
+            .iter()
+            .filter(|joint| {
+                !skeleton
+                    .joints
+                    .iter()
+                    .any(|skeleton_joint| skeleton_joint.hash == joint.hash)
+            })
+            .cloned(),
+    );
+
+    Animation {
+        fps: animation.fps,
+        duration: animation.duration,
+        frame_delay: animation.frame_delay,
+        joints,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lol::{anm::sample_joint, hasher, skl};
+
+    const FRAME_DELAY: f32 = 0.1f32;
+
+    fn test_joint(name: &str, id: i16, parent_id: i16, translation: glam::Vec3) -> skl::Joint {
+        let local_matrix = glam::Mat4::from_translation(translation);
+        skl::Joint {
+            name: name.to_owned(),
+            hash: hasher::string_to_hash(name),
+            id,
+            parent_id,
+            local_matrix,
+            global_matrix: local_matrix,
+            inverse_global_matrix: local_matrix.inverse(),
+            children: vec![],
+        }
+    }
+
+    fn test_skeleton() -> Skeleton {
+        Skeleton {
+            stype: skl::Type::Version2,
+            version: 0,
+            joints: vec![
+                test_joint("Root", 0, -1, glam::Vec3::ZERO),
+                test_joint("L_Arm", 1, 0, glam::vec3(1.0f32, 0.0f32, 0.0f32)),
+                test_joint("R_Arm", 2, 0, glam::vec3(-1.0f32, 0.0f32, 0.0f32)),
+                test_joint("L_Tail", 3, 0, glam::vec3(0.0f32, 0.0f32, -1.0f32)),
+            ],
+            influences: vec![],
+        }
+    }
+
+    fn track(name: &str, keys: &[(f32, glam::Vec3, glam::Quat)]) -> JointAnm {
+        JointAnm {
+            hash: hasher::string_to_hash(name),
+            name: None,
+            translations: keys
+                .iter()
+                .map(|(time, translation, _)| (*time, *translation))
+                .collect(),
+            rotations: keys
+                .iter()
+                .map(|(time, _, rotation)| (*time, *rotation))
+                .collect(),
+            scales: keys
+                .iter()
+                .map(|(time, _, _)| (*time, glam::Vec3::ONE))
+                .collect(),
+        }
+    }
+
+    fn test_animation() -> Animation {
+        Animation {
+            fps: 1.0f32 / FRAME_DELAY,
+            duration: 2.0f32 * FRAME_DELAY,
+            frame_delay: FRAME_DELAY,
+            joints: vec![
+                track(
+                    "L_Arm",
+                    &[
+                        (
+                            0.0f32,
+                            glam::vec3(1.0f32, 0.0f32, 0.0f32),
+                            glam::Quat::IDENTITY,
+                        ),
+                        (
+                            2.0f32 * FRAME_DELAY,
+                            glam::vec3(1.0f32, 1.0f32, 0.0f32),
+                            glam::Quat::from_rotation_z(0.4f32),
+                        ),
+                    ],
+                ),
+                track(
+                    "R_Arm",
+                    &[(
+                        0.0f32,
+                        glam::vec3(-1.0f32, 0.0f32, 0.0f32),
+                        glam::Quat::IDENTITY,
+                    )],
+                ),
+            ],
+        }
+    }
+
+    fn find_track<'a>(animation: &'a Animation, name: &str) -> &'a JointAnm {
+        let hash = hasher::string_to_hash(name);
+        animation
+            .joints
+            .iter()
+            .find(|joint| joint.hash == hash)
+            .unwrap()
+    }
+
+    fn assert_pose_eq(actual: &JointPose, expected: &JointPose) {
+        assert!(
+            actual
+                .translation
+                .abs_diff_eq(expected.translation, 0.0001f32)
+                && actual.rotation.abs_diff_eq(expected.rotation, 0.0001f32),
+            "{:?} {:?} != {:?} {:?}",
+            actual.translation,
+            actual.rotation,
+            expected.translation,
+            expected.rotation
+        );
+    }
+
+    #[test]
+    fn mirror_pairs_match_left_and_right() {
+        let mirror_pairs = MirrorPairs::new(&test_skeleton());
+
+        assert_eq!(mirror_pairs.pairs, vec![0, 2, 1, 3]);
+        assert_eq!(mirror_pairs.paired_count(), 2);
+        assert_eq!(mirror_pairs.unpaired, vec![String::from("L_Tail")]);
+    }
+
+    #[test]
+    fn mirror_swaps_paired_tracks() {
+        let skeleton = test_skeleton();
+        let mirrored = mirror_animation(
+            &test_animation(),
+            &skeleton,
+            &MirrorPairs::new(&skeleton),
+            MirrorAxis::X,
+        );
+
+        let time = 2.0f32 * FRAME_DELAY;
+        assert_pose_eq(
+            &sample_joint(find_track(&mirrored, "R_Arm"), time),
+            &JointPose {
+                translation: glam::vec3(-1.0f32, 1.0f32, 0.0f32),
+                rotation: glam::Quat::from_rotation_z(-0.4f32),
+                scale: glam::Vec3::ONE,
+            },
+        );
+        assert_pose_eq(
+            &sample_joint(find_track(&mirrored, "L_Arm"), time),
+            &JointPose::from_matrix(&skeleton.joints[1].local_matrix),
+        );
+    }
+
+    #[test]
+    fn mirror_keeps_boundary_keys() {
+        let skeleton = test_skeleton();
+        let animation = test_animation();
+        let mirrored = mirror_animation(
+            &animation,
+            &skeleton,
+            &MirrorPairs::new(&skeleton),
+            MirrorAxis::X,
+        );
+
+        assert_eq!(mirrored.duration, animation.duration);
+        for joint in mirrored.joints.iter() {
+            let times: Vec<f32> = joint.translations.iter().map(|(time, _)| *time).collect();
+            assert_eq!(times.len(), 3);
+            assert_eq!(times[0], 0.0f32);
+            assert_eq!(times[times.len() - 1], animation.duration);
+        }
+    }
+
+    #[test]
+    fn mirror_twice_is_identity() {
+        let skeleton = test_skeleton();
+        let mirror_pairs = MirrorPairs::new(&skeleton);
+        let animation = test_animation();
+        let mirrored = mirror_animation(
+            &mirror_animation(&animation, &skeleton, &mirror_pairs, MirrorAxis::X),
+            &skeleton,
+            &mirror_pairs,
+            MirrorAxis::X,
+        );
+
+        for name in ["L_Arm", "R_Arm"] {
+            for frame in 0..3 {
+                let time = frame as f32 * FRAME_DELAY;
+                assert_pose_eq(
+                    &sample_joint(find_track(&mirrored, name), time),
+                    &sample_joint(find_track(&animation, name), time),
+                );
+            }
+        }
+    }
+}
@@ -1,7 +1,18 @@
 pub mod anm;
+pub mod compare;
+pub mod diagnostics;
 pub mod hasher;
+pub mod ik;
+pub mod layer;
+pub mod mirror;
+pub mod picking;
+pub mod posing;
+pub mod rebind;
+pub mod retarget;
+pub mod root_motion;
 pub mod skl;
 pub mod skn;
+pub mod validation;
 
 pub use self::anm::Animation;
 pub use self::skl::Skeleton;
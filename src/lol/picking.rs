@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+
+use crate::lol::Skin;
+
+pub fn project_to_screen(
+    projection_view_matrix: &glam::Mat4,
+    [width, height]: [f32; 2],
+    position: glam::Vec3,
+) -> Option<glam::Vec2> {
+    let clip = *projection_view_matrix * position.extend(1.0f32);
+    if clip.w <= 0.0f32 {
+        return None;
+    }
+    Some(glam::vec2(
+        (clip.x / clip.w + 1.0f32) / 2.0f32 * width,
+        (1.0f32 - clip.y / clip.w) / 2.0f32 * height,
+    ))
+}
+
+pub fn unproject_from_screen(
+    projection_view_matrix: &glam::Mat4,
+    [width, height]: [f32; 2],
+    screen: glam::Vec2,
+    depth_position: glam::Vec3,
+) -> glam::Vec3 {
+    let clip = *projection_view_matrix * depth_position.extend(1.0f32);
+    let ndc = glam::vec2(
+        screen.x / width * 2.0f32 - 1.0f32,
+        1.0f32 - screen.y / height * 2.0f32,
+    );
+    let position = projection_view_matrix.inverse()
+        * glam::vec4(ndc.x * clip.w, ndc.y * clip.w, clip.z, clip.w);
+    position.truncate() / position.w
+}
+
+pub fn ray_from_screen(
+    projection_view_matrix: &glam::Mat4,
+    [width, height]: [f32; 2],
+    screen: glam::Vec2,
+) -> (glam::Vec3, glam::Vec3) {
+    let ndc = glam::vec2(
+        screen.x / width * 2.0f32 - 1.0f32,
+        1.0f32 - screen.y / height * 2.0f32,
+    );
+    let inverse = projection_view_matrix.inverse();
+    let near = inverse * glam::vec4(ndc.x, ndc.y, -1.0f32, 1.0f32);
+    let far = inverse * glam::vec4(ndc.x, ndc.y, 1.0f32, 1.0f32);
+    let near = near.truncate() / near.w;
+    // The infinite projection puts the far plane at infinity, leaving a direction
+    let direction = if far.w.abs() > f32::EPSILON {
+        far.truncate() / far.w - near
+    } else {
+        far.truncate()
+    };
+    (near, direction.normalize_or_zero())
+}
+
+pub fn skinned_positions(
+    skin: &Skin,
+    joint_transforms: &[glam::Mat4],
+    skinned: bool,
+) -> Vec<glam::Vec3> {
+    if !skinned {
+        return skin.vertices.to_owned();
+    }
+
+    (0..skin.vertices.len())
+        .map(|i| {
+            skin.skin_matrix(joint_transforms, i)
+                .transform_point3(skin.vertices[i])
+        })
+        .collect()
+}
+
+pub fn ray_triangle(
+    origin: glam::Vec3,
+    direction: glam::Vec3,
+    [a, b, c]: [glam::Vec3; 3],
+) -> Option<f32> {
+    let edge_ab = b - a;
+    let edge_ac = c - a;
+    let p = direction.cross(edge_ac);
+    let determinant = edge_ab.dot(p);
+    if determinant.abs() <= f32::EPSILON {
+        return None;
+    }
+
+    let inverse_determinant = 1.0f32 / determinant;
+    let t = origin - a;
+    let u = t.dot(p) * inverse_determinant;
+    if !(0.0f32..=1.0f32).contains(&u) {
+        return None;
+    }
+
+    let q = t.cross(edge_ab);
+    let v = direction.dot(q) * inverse_determinant;
+    if v < 0.0f32 || u + v > 1.0f32 {
+        return None;
+    }
+
+    let distance = edge_ac.dot(q) * inverse_determinant;
+    (distance > 0.0f32).then_some(distance)
+}
+
+pub fn pick_mesh(
+    skin: &Skin,
+    positions: &[glam::Vec3],
+    show_meshes: &[bool],
+    origin: glam::Vec3,
+    direction: glam::Vec3,
+) -> Option<(usize, glam::Vec3)> {
+    let mut nearest: Option<(usize, f32)> = None;
+
+    for (i, mesh) in skin.meshes.iter().enumerate() {
+        if !show_meshes[i] {
+            continue;
+        }
+
+        let start = mesh.submesh.indices_offset as usize;
+        let end = start + mesh.submesh.indices_count as usize;
+        for triangle in skin.indices[start..end].chunks_exact(3) {
+            let triangle = [
+                positions[triangle[0] as usize],
+                positions[triangle[1] as usize],
+                positions[triangle[2] as usize],
+            ];
+            if let Some(distance) = ray_triangle(origin, direction, triangle) {
+                if !matches!(nearest, Some((_, nearest_distance)) if nearest_distance <= distance) {
+                    nearest = Some((i, distance));
+                }
+            }
+        }
+    }
+
+    nearest.map(|(mesh, distance)| (mesh, origin + direction * distance))
+}
+
+pub struct MeshOutline {
+    pub mesh: usize,
+    triangles: Vec<[u16; 3]>,
+    edges: Vec<(u16, u16, usize, Option<usize>)>,
+}
+
+impl MeshOutline {
+    pub fn new(skin: &Skin, mesh: usize) -> MeshOutline {
+        let mut welded: HashMap<[u32; 3], u16> = HashMap::new();
+        let mut weld = |index: u16| {
+            let position = skin.vertices[index as usize];
+            *welded
+                .entry(position.to_array().map(f32::to_bits))
+                .or_insert(index)
+        };
+
+        let submesh = &skin.meshes[mesh].submesh;
+        let start = submesh.indices_offset as usize;
+        let end = start + submesh.indices_count as usize;
+        let triangles: Vec<[u16; 3]> = skin.indices[start..end]
+            .chunks_exact(3)
+            .map(|triangle| [weld(triangle[0]), weld(triangle[1]), weld(triangle[2])])
+            .collect();
+
+        let mut edge_map: HashMap<(u16, u16), usize> = HashMap::new();
+        let mut edges: Vec<(u16, u16, usize, Option<usize>)> = vec![];
+        for (i, triangle) in triangles.iter().enumerate() {
+            for (a, b) in [
+                (triangle[0], triangle[1]),
+                (triangle[1], triangle[2]),
+                (triangle[2], triangle[0]),
+            ] {
+                if a == b {
+                    continue;
+                }
+                let key = (a.min(b), a.max(b));
+                match edge_map.get(&key) {
+                    Some(edge) if edges[*edge].3.is_none() => edges[*edge].3 = Some(i),
+                    Some(_) => {}
+                    None => {
+                        edge_map.insert(key, edges.len());
+                        edges.push((key.0, key.1, i, None));
+                    }
+                }
+            }
+        }
+
+        MeshOutline {
+            mesh,
+            triangles,
+            edges,
+        }
+    }
+
+    pub fn lines(&self, positions: &[glam::Vec3], camera_position: glam::Vec3) -> Vec<glam::Vec4> {
+        let facing: Vec<bool> = self
+            .triangles
+            .iter()
+            .map(|triangle| {
+                let a = positions[triangle[0] as usize];
+                let b = positions[triangle[1] as usize];
+                let c = positions[triangle[2] as usize];
+                (b - a).cross(c - a).dot(camera_position - a) > 0.0f32
+            })
+            .collect();
+
+        self.edges
+            .iter()
+            .filter(|(_, _, first, second)| match second {
+                Some(second) => facing[*first] != facing[*second],
+                None => true,
+            })
+            .flat_map(|(a, b, _, _)| {
+                [
+                    positions[*a as usize].extend(1.0f32),
+                    positions[*b as usize].extend(1.0f32),
+                ]
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,83 @@
+use crate::lol::{
+    anm::{apply_pose, pose_from_transforms, JointAnm, JointPose},
+    Animation, Skeleton,
+};
+
+pub struct Posing {
+    pub enabled: bool,
+    pub offsets: Vec<JointPose>,
+}
+
+impl Posing {
+    pub fn new(skeleton: &Skeleton) -> Posing {
+        Posing {
+            enabled: false,
+            offsets: vec![JointPose::IDENTITY; skeleton.joints.len()],
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.offsets.fill(JointPose::IDENTITY);
+    }
+
+    pub fn is_posed(&self, joint_index: usize) -> bool {
+        let offset = &self.offsets[joint_index];
+        offset.translation != glam::Vec3::ZERO || offset.rotation != glam::Quat::IDENTITY
+    }
+
+    pub fn apply(&self, pose: &mut [JointPose]) {
+        for (joint_pose, offset) in pose.iter_mut().zip(self.offsets.iter()) {
+            joint_pose.translation += offset.translation;
+            joint_pose.rotation = (joint_pose.rotation * offset.rotation).normalize();
+        }
+    }
+
+    pub fn apply_bind_pose(&self, joint_transforms: &mut [glam::Mat4], skeleton: &Skeleton) {
+        let mut pose = bind_pose(skeleton);
+        self.apply(&mut pose);
+        apply_pose(joint_transforms, &pose, skeleton);
+    }
+}
+
+pub fn bind_pose(skeleton: &Skeleton) -> Vec<JointPose> {
+    skeleton
+        .joints
+        .iter()
+        .map(|joint| JointPose::from_matrix(&joint.local_matrix))
+        .collect()
+}
+
+pub fn current_pose(
+    joint_transforms: &[glam::Mat4],
+    skeleton: &Skeleton,
+    skinned: bool,
+) -> Vec<JointPose> {
+    if skinned {
+        pose_from_transforms(joint_transforms, skeleton)
+    } else {
+        bind_pose(skeleton)
+    }
+}
+
+pub fn pose_animation(pose: &[JointPose], skeleton: &Skeleton) -> Animation {
+    let fps = 30.0f32;
+    let frame_delay = 1.0f32 / fps;
+
+    Animation {
+        fps,
+        duration: frame_delay,
+        frame_delay,
+        joints: skeleton
+            .joints
+            .iter()
+            .zip(pose.iter())
+            .map(|(joint, joint_pose)| JointAnm {
+                hash: joint.hash,
+                name: Some(joint.name.to_owned()),
+                translations: vec![(0.0f32, joint_pose.translation)],
+                rotations: vec![(0.0f32, joint_pose.rotation)],
+                scales: vec![(0.0f32, joint_pose.scale)],
+            })
+            .collect(),
+    }
+}
@@ -0,0 +1,99 @@
+use crate::lol::{
+    skl::{Joint, Type},
+    skn::{Mesh, SubMeshHeader},
+    Skeleton, Skin,
+};
+
+pub fn bake_skin(skin: &Skin, joint_transforms: &[glam::Mat4]) -> Skin {
+    let mut vertices: Vec<glam::Vec3> = Vec::with_capacity(skin.vertices.len());
+    let mut normals: Vec<glam::Vec3> = Vec::with_capacity(skin.normals.len());
+
+    for i in 0..skin.vertices.len() {
+        let skin_matrix = skin.skin_matrix(joint_transforms, i);
+
+        vertices.push(skin_matrix.transform_point3(skin.vertices[i]));
+        normals.push(
+            skin_matrix
+                .inverse()
+                .transpose()
+                .transform_vector3(skin.normals[i])
+                .normalize_or_zero(),
+        );
+    }
+
+    let mut bbmin = glam::Vec3::splat(f32::MAX);
+    let mut bbmax = glam::Vec3::splat(f32::MIN);
+    for vertex in vertices.iter() {
+        bbmin = bbmin.min(*vertex);
+        bbmax = bbmax.max(*vertex);
+    }
+
+    Skin {
+        major: skin.major,
+        minor: skin.minor,
+        center: (bbmin + bbmax) / 2.0f32,
+        bounding_box: [bbmin, bbmax],
+        vertices,
+        normals,
+        uvs: skin.uvs.to_owned(),
+        influences: skin.influences.to_owned(),
+        weights: skin.weights.to_owned(),
+        indices: skin.indices.to_owned(),
+        meshes: skin
+            .meshes
+            .iter()
+            .map(|mesh| Mesh {
+                hash: mesh.hash,
+                submesh: SubMeshHeader {
+                    name: mesh.submesh.name.to_owned(),
+                    indices_offset: mesh.submesh.indices_offset,
+                    indices_count: mesh.submesh.indices_count,
+                },
+            })
+            .collect(),
+    }
+}
+
+pub fn rebind_skeleton(skeleton: &Skeleton, joint_transforms: &[glam::Mat4]) -> Skeleton {
+    let global_matrices: Vec<glam::Mat4> = skeleton
+        .joints
+        .iter()
+        .enumerate()
+        .map(|(i, joint)| joint_transforms[i] * joint.global_matrix)
+        .collect();
+
+    let joints = skeleton
+        .joints
+        .iter()
+        .enumerate()
+        .map(|(i, joint)| {
+            let global_matrix = global_matrices[i];
+            let local_matrix = if joint.parent_id != -1 {
+                global_matrices[joint.parent_id as usize].inverse() * global_matrix
+            } else {
+                global_matrix
+            };
+
+            Joint {
+                name: joint.name.to_owned(),
+                hash: joint.hash,
+                id: joint.id,
+                parent_id: joint.parent_id,
+                local_matrix,
+                global_matrix,
+                inverse_global_matrix: global_matrix.inverse(),
+                children: joint.children.to_owned(),
+            }
+        })
+        .collect();
+
+    Skeleton {
+        stype: match skeleton.stype {
+            Type::Classic => Type::Classic,
+            Type::Version2 => Type::Version2,
+        },
+        version: skeleton.version,
+        joints,
+        influences: skeleton.influences.to_owned(),
+    }
+}
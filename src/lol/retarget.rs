@@ -0,0 +1,136 @@
+use crate::lol::{
+    anm::{JointAnm, JointPose},
+    Animation, Skeleton,
+};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum MatchBy {
+    Hash,
+    Name,
+}
+
+pub struct Retarget {
+    pub skeleton: Skeleton,
+    pub skeleton_path: String,
+    pub animations_path: String,
+    pub mapping: Vec<Option<usize>>,
+    pub animation_count: usize,
+}
+
+impl Retarget {
+    pub fn new(
+        skeleton: Skeleton,
+        skeleton_path: String,
+        animations_path: String,
+        target: &Skeleton,
+    ) -> Retarget {
+        let mut retarget = Retarget {
+            skeleton,
+            skeleton_path,
+            animations_path,
+            mapping: vec![None; target.joints.len()],
+            animation_count: 0,
+        };
+        retarget.auto_map(target, MatchBy::Hash);
+        retarget.auto_map(target, MatchBy::Name);
+        retarget
+    }
+
+    pub fn auto_map(&mut self, target: &Skeleton, match_by: MatchBy) {
+        for (i, target_joint) in target.joints.iter().enumerate() {
+            if self.mapping[i].is_some() {
+                continue;
+            }
+            self.mapping[i] = self
+                .skeleton
+                .joints
+                .iter()
+                .position(|source_joint| match match_by {
+                    MatchBy::Hash => source_joint.hash == target_joint.hash,
+                    MatchBy::Name => source_joint.name.eq_ignore_ascii_case(&target_joint.name),
+                });
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.mapping.iter_mut().for_each(|mapping| *mapping = None);
+    }
+
+    pub fn mapped_count(&self) -> usize {
+        self.mapping
+            .iter()
+            .filter(|mapping| mapping.is_some())
+            .count()
+    }
+
+    pub fn retarget_animation(&self, animation: &Animation, target: &Skeleton) -> Animation {
+        let mut joints = Vec::with_capacity(target.joints.len());
+
+        for (i, target_joint) in target.joints.iter().enumerate() {
+            let Some(source_index) = self.mapping[i] else {
+                continue;
+            };
+
+            let source_joint = &self.skeleton.joints[source_index];
+            let Some(source_track) = animation
+                .joints
+                .iter()
+                .find(|joint| joint.hash == source_joint.hash)
+            else {
+                continue;
+            };
+
+            let source_bind = JointPose::from_matrix(&source_joint.local_matrix);
+            let target_bind = JointPose::from_matrix(&target_joint.local_matrix);
+
+            let source_length = source_bind.translation.length();
+            let bone_ratio = if source_length > f32::EPSILON {
+                target_bind.translation.length() / source_length
+            } else {
+                1.0f32
+            };
+
+            let source_bind_rotation_inverse = source_bind.rotation.inverse();
+
+            joints.push(JointAnm {
+                hash: target_joint.hash,
+                name: Some(target_joint.name.to_owned()),
+                translations: source_track
+                    .translations
+                    .iter()
+                    .map(|(time, translation)| {
+                        let delta = *translation - source_bind.translation;
+                        (*time, target_bind.translation + delta * bone_ratio)
+                    })
+                    .collect(),
+                rotations: source_track
+                    .rotations
+                    .iter()
+                    .map(|(time, rotation)| {
+                        let delta = source_bind_rotation_inverse * *rotation;
+                        (*time, (target_bind.rotation * delta).normalize())
+                    })
+                    .collect(),
+                scales: source_track
+                    .scales
+                    .iter()
+                    .map(|(time, scale)| {
+                        let delta = if source_bind.scale.cmpne(glam::Vec3::ZERO).all() {
+                            *scale / source_bind.scale
+                        } else {
+                            glam::Vec3::ONE
+                        };
+                        (*time, target_bind.scale * delta)
+                    })
+                    .collect(),
+            });
+        }
+
+        Animation {
+            fps: animation.fps,
+            duration: animation.duration,
+            frame_delay: animation.frame_delay,
+            joints,
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use crate::lol::{
+    anm::{self, JointPose},
+    Animation, Skeleton,
+};
+
+pub struct RootMotion {
+    pub root: Option<usize>,
+    pub offset: glam::Mat4,
+    pub path: Vec<glam::Vec3>,
+    pub path_animation: Option<usize>,
+}
+
+impl RootMotion {
+    pub fn new(skeleton: &Skeleton) -> RootMotion {
+        RootMotion {
+            root: skeleton
+                .joints
+                .iter()
+                .position(|joint| joint.parent_id == -1),
+            offset: glam::Mat4::IDENTITY,
+            path: vec![],
+            path_animation: None,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.offset = glam::Mat4::IDENTITY;
+    }
+
+    pub fn accumulate(&mut self, animation: &Animation, skeleton: &Skeleton, start: f32, end: f32) {
+        if let Some(root) = self.root {
+            self.offset *= root_delta(animation, skeleton, root, start).inverse()
+                * root_delta(animation, skeleton, root, end);
+        }
+    }
+
+    pub fn update_path(
+        &mut self,
+        animation: &Animation,
+        animation_selected: usize,
+        skeleton: &Skeleton,
+    ) {
+        if self.path_animation == Some(animation_selected) {
+            return;
+        }
+        self.path_animation = Some(animation_selected);
+
+        self.path.clear();
+        if let Some(root) = self.root {
+            let frame_count = (animation.duration / animation.frame_delay).ceil() as usize;
+            for i in 0..=frame_count {
+                let time = (animation.frame_delay * i as f32).min(animation.duration);
+                let root_pose = sample_root(animation, skeleton, root, time);
+                self.path.push(glam::vec3(
+                    root_pose.translation.x,
+                    1.0f32,
+                    root_pose.translation.z,
+                ));
+            }
+        }
+    }
+}
+
+fn sample_root(animation: &Animation, skeleton: &Skeleton, root: usize, time: f32) -> JointPose {
+    let skeleton_joint = &skeleton.joints[root];
+    match animation
+        .joints
+        .iter()
+        .find(|&joint| joint.hash == skeleton_joint.hash)
+    {
+        Some(joint) => anm::sample_joint(joint, time),
+        None => JointPose::from_matrix(&skeleton_joint.local_matrix),
+    }
+}
+
+fn yaw_twist(rotation: glam::Quat) -> glam::Quat {
+    let twist = glam::quat(0.0f32, rotation.y, 0.0f32, rotation.w);
+    if twist.length_squared() > f32::EPSILON {
+        twist.normalize()
+    } else {
+        glam::Quat::IDENTITY
+    }
+}
+
+pub fn root_delta(
+    animation: &Animation,
+    skeleton: &Skeleton,
+    root: usize,
+    time: f32,
+) -> glam::Mat4 {
+    let start = sample_root(animation, skeleton, root, 0.0f32);
+    let current = sample_root(animation, skeleton, root, time);
+
+    let translation = current.translation - start.translation;
+    let rotation = yaw_twist(start.rotation.inverse() * current.rotation);
+
+    glam::Mat4::from_rotation_translation(
+        rotation,
+        glam::vec3(translation.x, 0.0f32, translation.z),
+    )
+}
+
+pub fn lock_root(pose: &mut [JointPose], animation: &Animation, skeleton: &Skeleton, root: usize) {
+    let start = sample_root(animation, skeleton, root, 0.0f32);
+
+    let root_pose = &mut pose[root];
+    root_pose.translation.x = start.translation.x;
+    root_pose.translation.z = start.translation.z;
+
+    let delta = start.rotation.inverse() * root_pose.rotation;
+    let swing = yaw_twist(delta).inverse() * delta;
+    root_pose.rotation = (start.rotation * swing).normalize();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lol::{anm::JointAnm, hasher, skl};
+
+    const DURATION: f32 = 1.0f32;
+
+    fn test_skeleton() -> Skeleton {
+        let local_matrix = glam::Mat4::from_translation(glam::vec3(0.0f32, 1.0f32, 0.0f32));
+        Skeleton {
+            stype: skl::Type::Version2,
+            version: 0,
+            joints: vec![skl::Joint {
+                name: "Root".to_owned(),
+                hash: hasher::string_to_hash("Root"),
+                id: 0,
+                parent_id: -1,
+                local_matrix,
+                global_matrix: local_matrix,
+                inverse_global_matrix: local_matrix.inverse(),
+                children: vec![],
+            }],
+            influences: vec![],
+        }
+    }
+
+    fn walk_animation() -> Animation {
+        Animation {
+            fps: 10.0f32,
+            duration: DURATION,
+            frame_delay: 0.1f32,
+            joints: vec![JointAnm {
+                hash: hasher::string_to_hash("Root"),
+                name: None,
+                translations: vec![
+                    (0.0f32, glam::vec3(0.0f32, 1.0f32, 0.0f32)),
+                    (DURATION, glam::vec3(2.0f32, 1.0f32, 3.0f32)),
+                ],
+                rotations: vec![
+                    (0.0f32, glam::Quat::IDENTITY),
+                    (DURATION, glam::Quat::from_rotation_y(0.5f32)),
+                ],
+                scales: vec![(0.0f32, glam::Vec3::ONE)],
+            }],
+        }
+    }
+
+    fn locked_root(animation: &Animation, skeleton: &Skeleton, time: f32) -> JointPose {
+        let mut pose = anm::sample_pose(animation, skeleton, time);
+        lock_root(&mut pose, animation, skeleton, 0);
+        pose[0]
+    }
+
+    #[test]
+    fn locked_root_stays_in_place_across_loop_wrap() {
+        let skeleton = test_skeleton();
+        let animation = walk_animation();
+        let root_motion = RootMotion::new(&skeleton);
+
+        let before_wrap = locked_root(&animation, &skeleton, DURATION);
+        let after_wrap = locked_root(&animation, &skeleton, 0.0f32);
+
+        assert_eq!(root_motion.offset, glam::Mat4::IDENTITY);
+        assert!(before_wrap
+            .translation
+            .abs_diff_eq(after_wrap.translation, 1e-5f32));
+        assert!(before_wrap
+            .rotation
+            .abs_diff_eq(after_wrap.rotation, 1e-5f32));
+    }
+
+    #[test]
+    fn accumulate_continues_root_across_loop_wrap() {
+        let skeleton = test_skeleton();
+        let animation = walk_animation();
+        let mut root_motion = RootMotion::new(&skeleton);
+
+        let end = anm::sample_joint(&animation.joints[0], DURATION);
+        root_motion.accumulate(&animation, &skeleton, 0.0f32, DURATION);
+        let start = anm::sample_joint(&animation.joints[0], 0.0f32);
+
+        assert!(root_motion
+            .offset
+            .transform_point3(start.translation)
+            .abs_diff_eq(end.translation, 1e-5f32));
+    }
+}
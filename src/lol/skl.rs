@@ -65,6 +65,16 @@ impl Skeleton {
         skeleton
     }
 
+    pub fn subtree(&self, joint_index: usize) -> Vec<usize> {
+        let mut subtree = vec![];
+        let mut stack = vec![joint_index];
+        while let Some(index) = stack.pop() {
+            subtree.push(index);
+            stack.extend_from_slice(&self.joints[index].children);
+        }
+        subtree
+    }
+
     fn read_classic(reader: &mut Cursor<&Vec<u8>>) -> Skeleton {
         let mut signature = vec![0u8; 8];
         reader
@@ -306,3 +316,26 @@ impl Skeleton {
         }
     }
 }
+
+pub fn joint_position(global_matrix: &glam::Mat4) -> glam::Vec3 {
+    global_matrix.w_axis.truncate()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn joint_position_ignores_rotation_and_scale() {
+        let global_matrix = glam::Mat4::from_scale_rotation_translation(
+            glam::vec3(2.0f32, 3.0f32, 4.0f32),
+            glam::Quat::from_rotation_y(0.7f32),
+            glam::vec3(1.0f32, -2.0f32, 5.0f32),
+        );
+
+        assert_eq!(
+            joint_position(&global_matrix),
+            glam::vec3(1.0f32, -2.0f32, 5.0f32)
+        );
+    }
+}
@@ -26,6 +26,73 @@ impl Mesh {
     }
 }
 
+pub struct SkinVectors {
+    pub tangents: Vec<glam::Vec3>,
+    pub flipped_normals: Vec<bool>,
+}
+
+impl SkinVectors {
+    pub fn new(skin: &Skin) -> SkinVectors {
+        let mut tangents = vec![glam::Vec3::ZERO; skin.vertices.len()];
+        let mut face_normals = vec![glam::Vec3::ZERO; skin.vertices.len()];
+
+        for triangle in skin.indices.chunks_exact(3) {
+            let [a, b, c] = [
+                triangle[0] as usize,
+                triangle[1] as usize,
+                triangle[2] as usize,
+            ];
+            if a.max(b).max(c) >= skin.vertices.len() {
+                continue;
+            }
+
+            let edge_ab = skin.vertices[b] - skin.vertices[a];
+            let edge_ac = skin.vertices[c] - skin.vertices[a];
+            let face_normal = edge_ab.cross(edge_ac);
+
+            let uv_ab = skin.uvs[b] - skin.uvs[a];
+            let uv_ac = skin.uvs[c] - skin.uvs[a];
+            let determinant = uv_ab.x * uv_ac.y - uv_ac.x * uv_ab.y;
+            let tangent = if determinant.abs() > f32::EPSILON {
+                (edge_ab * uv_ac.y - edge_ac * uv_ab.y) / determinant
+            } else {
+                glam::Vec3::ZERO
+            };
+
+            for vertex in [a, b, c] {
+                tangents[vertex] += tangent;
+                face_normals[vertex] += face_normal;
+            }
+        }
+
+        let tangents = tangents
+            .iter()
+            .zip(skin.normals.iter())
+            .map(|(tangent, normal)| {
+                let normal = normal.normalize_or_zero();
+                (*tangent - normal * normal.dot(*tangent)).normalize_or_zero()
+            })
+            .collect();
+
+        let facings: Vec<f32> = face_normals
+            .iter()
+            .zip(skin.normals.iter())
+            .map(|(face_normal, normal)| face_normal.dot(*normal))
+            .collect();
+        let backward = facings.iter().filter(|facing| **facing < 0.0f32).count();
+        let forward = facings.iter().filter(|facing| **facing > 0.0f32).count();
+        let winding = if backward > forward { -1.0f32 } else { 1.0f32 };
+
+        SkinVectors {
+            tangents,
+            flipped_normals: facings
+                .iter()
+                .map(|facing| facing * winding < 0.0f32)
+                .collect(),
+        }
+    }
+}
+
 pub struct Skin {
     pub major: u16,
     pub minor: u16,
@@ -196,6 +263,15 @@ impl Skin {
         }
     }
 
+    pub fn skin_matrix(&self, joint_transforms: &[glam::Mat4], vertex: usize) -> glam::Mat4 {
+        let influence = self.influences[vertex];
+        let weight = self.weights[vertex];
+        joint_transforms[influence.x as usize] * weight.x
+            + joint_transforms[influence.y as usize] * weight.y
+            + joint_transforms[influence.z as usize] * weight.z
+            + joint_transforms[influence.w as usize] * weight.w
+    }
+
     pub fn apply_skeleton(&mut self, skeleton: &Skeleton) {
         for skin_influence in self.influences.iter_mut() {
             skin_influence.x = skeleton.influences[skin_influence.x as usize];
@@ -0,0 +1,415 @@
+use serde::Serialize;
+use std::{collections::HashMap, fmt, fs, path::Path};
+
+use crate::lol::{Skeleton, Skin};
+
+const MAX_JOINT_MATRICES: usize = 256;
+const WEIGHT_SUM_EPSILON: f32 = 0.001f32;
+const DEGENERATE_AREA_EPSILON: f32 = 0.000001f32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct TriangleRef {
+    #[serde(rename = "Submesh")]
+    pub submesh: usize,
+
+    #[serde(rename = "Triangle")]
+    pub triangle: usize,
+}
+
+impl fmt::Display for TriangleRef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {})", self.submesh, self.triangle)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SubmeshReport {
+    #[serde(rename = "Name")]
+    pub name: String,
+
+    #[serde(rename = "IndicesOffset")]
+    pub indices_offset: u32,
+
+    #[serde(rename = "IndicesCount")]
+    pub indices_count: u32,
+
+    #[serde(rename = "RangeOutOfBounds")]
+    pub range_out_of_bounds: bool,
+
+    #[serde(rename = "MisalignedOffset")]
+    pub misaligned_offset: bool,
+
+    #[serde(rename = "OutOfRangeIndices")]
+    pub out_of_range_indices: Vec<usize>,
+
+    #[serde(rename = "DegenerateTriangles")]
+    pub degenerate_triangles: Vec<TriangleRef>,
+
+    #[serde(rename = "DuplicateTriangles")]
+    pub duplicate_triangles: Vec<TriangleRef>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ValidationReport {
+    #[serde(rename = "Name")]
+    pub name: String,
+
+    #[serde(rename = "VertexCount")]
+    pub vertex_count: usize,
+
+    #[serde(rename = "IndexCount")]
+    pub index_count: usize,
+
+    #[serde(rename = "JointCount")]
+    pub joint_count: usize,
+
+    #[serde(rename = "EmptySubmeshes")]
+    pub empty_submeshes: Vec<String>,
+
+    #[serde(rename = "UnusedVertices")]
+    pub unused_vertices: Vec<usize>,
+
+    #[serde(rename = "UnnormalizedWeights")]
+    pub unnormalized_weights: Vec<usize>,
+
+    #[serde(rename = "InfluencesOutsideSkeleton")]
+    pub influences_outside_skeleton: Vec<usize>,
+
+    #[serde(rename = "InfluencesOverMatrixLimit")]
+    pub influences_over_matrix_limit: Vec<usize>,
+
+    #[serde(rename = "NanNormals")]
+    pub nan_normals: Vec<usize>,
+
+    #[serde(rename = "Submeshes")]
+    pub submeshes: Vec<SubmeshReport>,
+}
+
+impl ValidationReport {
+    pub fn new(name: &str, skin: &Skin, skeleton: &Skeleton) -> ValidationReport {
+        let vertex_count = skin.vertices.len();
+        let mut used_vertices = vec![false; vertex_count];
+        let mut triangles: HashMap<[u16; 3], TriangleRef> = HashMap::new();
+
+        let submeshes: Vec<SubmeshReport> = skin
+            .meshes
+            .iter()
+            .enumerate()
+            .map(|(submesh_index, mesh)| {
+                let submesh = &mesh.submesh;
+                let start = (submesh.indices_offset as usize).min(skin.indices.len());
+                let end = (submesh.indices_offset as usize + submesh.indices_count as usize)
+                    .min(skin.indices.len());
+
+                let mut report = SubmeshReport {
+                    name: submesh.name.to_owned(),
+                    indices_offset: submesh.indices_offset,
+                    indices_count: submesh.indices_count,
+                    range_out_of_bounds: end - start != submesh.indices_count as usize,
+                    misaligned_offset: submesh.indices_offset % 3 != 0,
+                    out_of_range_indices: vec![],
+                    degenerate_triangles: vec![],
+                    duplicate_triangles: vec![],
+                };
+
+                for (position, index) in skin.indices[start..end].iter().enumerate() {
+                    match used_vertices.get_mut(*index as usize) {
+                        Some(used) => *used = true,
+                        None => report.out_of_range_indices.push(start + position),
+                    }
+                }
+
+                // Triangles read from a misaligned offset would be made of unrelated indices
+                if report.misaligned_offset {
+                    return report;
+                }
+
+                for (i, triangle) in skin.indices[start..end].chunks_exact(3).enumerate() {
+                    let triangle_ref = TriangleRef {
+                        submesh: submesh_index,
+                        triangle: i,
+                    };
+                    if triangle.iter().any(|index| *index as usize >= vertex_count) {
+                        continue;
+                    }
+
+                    let [a, b, c] = [
+                        skin.vertices[triangle[0] as usize],
+                        skin.vertices[triangle[1] as usize],
+                        skin.vertices[triangle[2] as usize],
+                    ];
+                    if triangle[0] == triangle[1]
+                        || triangle[1] == triangle[2]
+                        || triangle[0] == triangle[2]
+                        || (b - a).cross(c - a).length() <= DEGENERATE_AREA_EPSILON
+                    {
+                        report.degenerate_triangles.push(triangle_ref);
+                    }
+
+                    let mut key = [triangle[0], triangle[1], triangle[2]];
+                    key.sort_unstable();
+                    if triangles.insert(key, triangle_ref).is_some() {
+                        report.duplicate_triangles.push(triangle_ref);
+                    }
+                }
+
+                report
+            })
+            .collect();
+
+        let joint_count = skeleton.joints.len();
+        let influenced_vertices = |limit: usize| -> Vec<usize> {
+            skin.influences
+                .iter()
+                .zip(skin.weights.iter())
+                .enumerate()
+                .filter(|(_, (influence, weight))| {
+                    [influence.x, influence.y, influence.z, influence.w]
+                        .iter()
+                        .zip(weight.to_array())
+                        .any(|(joint, weight)| weight != 0.0f32 && *joint as usize >= limit)
+                })
+                .map(|(vertex, _)| vertex)
+                .collect()
+        };
+
+        ValidationReport {
+            name: name.to_owned(),
+            vertex_count,
+            index_count: skin.indices.len(),
+            joint_count,
+            empty_submeshes: skin
+                .meshes
+                .iter()
+                .filter(|mesh| mesh.submesh.indices_count == 0)
+                .map(|mesh| mesh.submesh.name.to_owned())
+                .collect(),
+            unused_vertices: used_vertices
+                .iter()
+                .enumerate()
+                .filter(|(_, used)| !**used)
+                .map(|(vertex, _)| vertex)
+                .collect(),
+            unnormalized_weights: skin
+                .weights
+                .iter()
+                .enumerate()
+                .filter(|(_, weight)| {
+                    (weight.to_array().iter().sum::<f32>() - 1.0f32).abs() > WEIGHT_SUM_EPSILON
+                })
+                .map(|(vertex, _)| vertex)
+                .collect(),
+            influences_outside_skeleton: influenced_vertices(joint_count),
+            influences_over_matrix_limit: influenced_vertices(MAX_JOINT_MATRICES),
+            nan_normals: skin
+                .normals
+                .iter()
+                .enumerate()
+                .filter(|(_, normal)| normal.is_nan())
+                .map(|(vertex, _)| vertex)
+                .collect(),
+            submeshes,
+        }
+    }
+
+    pub fn issue_count(&self) -> usize {
+        self.empty_submeshes.len()
+            + self.unused_vertices.len()
+            + self.unnormalized_weights.len()
+            + self.influences_outside_skeleton.len()
+            + self.influences_over_matrix_limit.len()
+            + self.nan_normals.len()
+            + self
+                .submeshes
+                .iter()
+                .map(|submesh| {
+                    submesh.range_out_of_bounds as usize
+                        + submesh.misaligned_offset as usize
+                        + submesh.out_of_range_indices.len()
+                        + submesh.degenerate_triangles.len()
+                        + submesh.duplicate_triangles.len()
+                })
+                .sum::<usize>()
+    }
+}
+
+pub fn write_report(path: &Path, report: &ValidationReport) {
+    println!("Writing validation report: {}", path.to_str().unwrap());
+
+    if let Some(parent) = path.parent() {
+        if let Err(error) = fs::create_dir_all(parent) {
+            return println!("Could not create validation dirs: {error}");
+        }
+    }
+
+    let contents = match serde_json::to_string_pretty(report) {
+        Ok(contents) => contents,
+        Err(error) => {
+            return println!("Could not serialize validation report: {error}");
+        }
+    };
+
+    if let Err(error) = fs::write(path, contents) {
+        println!("Could not write validation report: {error}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        gls::glam_read::U16Vec4,
+        lol::{
+            hasher, skl,
+            skn::{Mesh, SubMeshHeader},
+        },
+    };
+
+    fn test_skeleton() -> Skeleton {
+        Skeleton {
+            stype: skl::Type::Version2,
+            version: 0,
+            joints: ["Root", "Spine"]
+                .iter()
+                .enumerate()
+                .map(|(i, name)| skl::Joint {
+                    name: name.to_string(),
+                    hash: hasher::string_to_hash(name),
+                    id: i as i16,
+                    parent_id: i as i16 - 1,
+                    local_matrix: glam::Mat4::IDENTITY,
+                    global_matrix: glam::Mat4::IDENTITY,
+                    inverse_global_matrix: glam::Mat4::IDENTITY,
+                    children: vec![],
+                })
+                .collect(),
+            influences: vec![0, 1],
+        }
+    }
+
+    fn test_mesh(name: &str, indices_offset: u32, indices_count: u32) -> Mesh {
+        Mesh {
+            hash: 0,
+            submesh: SubMeshHeader {
+                name: name.to_owned(),
+                indices_offset,
+                indices_count,
+            },
+        }
+    }
+
+    fn test_skin() -> Skin {
+        Skin {
+            major: 4,
+            minor: 1,
+            center: glam::Vec3::ZERO,
+            bounding_box: [glam::Vec3::ZERO, glam::Vec3::ONE],
+            vertices: vec![
+                glam::vec3(0.0f32, 0.0f32, 0.0f32),
+                glam::vec3(1.0f32, 0.0f32, 0.0f32),
+                glam::vec3(0.0f32, 1.0f32, 0.0f32),
+                glam::vec3(1.0f32, 1.0f32, 0.0f32),
+                glam::vec3(2.0f32, 2.0f32, 0.0f32),
+            ],
+            normals: vec![
+                glam::Vec3::Z,
+                glam::Vec3::Z,
+                glam::Vec3::Z,
+                glam::Vec3::Z,
+                glam::Vec3::NAN,
+            ],
+            uvs: vec![glam::Vec2::ZERO; 5],
+            influences: vec![
+                U16Vec4::new(0, 0, 0, 0),
+                U16Vec4::new(300, 1, 0, 0),
+                U16Vec4::new(0, 5, 0, 0),
+                U16Vec4::new(3, 0, 0, 0),
+                U16Vec4::new(0, 0, 0, 0),
+            ],
+            weights: vec![
+                glam::vec4(1.0f32, 0.0f32, 0.0f32, 0.0f32),
+                glam::vec4(0.5f32, 0.4f32, 0.0f32, 0.0f32),
+                glam::vec4(1.0f32, 0.0f32, 0.0f32, 0.0f32),
+                glam::vec4(1.0f32, 0.0f32, 0.0f32, 0.0f32),
+                glam::vec4(1.0f32, 0.0f32, 0.0f32, 0.0f32),
+            ],
+            indices: vec![
+                0, 1, 2, 1, 3, 2, // Clean
+                2, 1, 0, 0, 0, 1, 1, 2, 9, // Broken
+                0, 1, 3, // Misaligned
+                3,
+            ],
+            meshes: vec![
+                test_mesh("Clean", 0, 6),
+                test_mesh("Broken", 6, 9),
+                test_mesh("Misaligned", 16, 3),
+                test_mesh("Empty", 18, 0),
+                test_mesh("OutOfBounds", 18, 6),
+            ],
+        }
+    }
+
+    #[test]
+    fn reports_vertex_issues() {
+        let report = ValidationReport::new("test", &test_skin(), &test_skeleton());
+
+        assert_eq!(report.vertex_count, 5);
+        assert_eq!(report.index_count, 19);
+        assert_eq!(report.joint_count, 2);
+        assert_eq!(report.empty_submeshes, vec![String::from("Empty")]);
+        assert_eq!(report.unused_vertices, vec![4]);
+        assert_eq!(report.unnormalized_weights, vec![1]);
+        assert_eq!(report.nan_normals, vec![4]);
+    }
+
+    #[test]
+    fn skips_zero_weight_influences() {
+        let report = ValidationReport::new("test", &test_skin(), &test_skeleton());
+
+        assert_eq!(report.influences_outside_skeleton, vec![1, 3]);
+        assert_eq!(report.influences_over_matrix_limit, vec![1]);
+    }
+
+    #[test]
+    fn reports_triangles_per_submesh() {
+        let report = ValidationReport::new("test", &test_skin(), &test_skeleton());
+        let clean = &report.submeshes[0];
+        let broken = &report.submeshes[1];
+
+        assert!(clean.out_of_range_indices.is_empty());
+        assert!(clean.degenerate_triangles.is_empty());
+        assert!(clean.duplicate_triangles.is_empty());
+
+        assert_eq!(broken.out_of_range_indices, vec![14]);
+        assert_eq!(
+            broken.degenerate_triangles,
+            vec![TriangleRef {
+                submesh: 1,
+                triangle: 1
+            }]
+        );
+        assert_eq!(
+            broken.duplicate_triangles,
+            vec![TriangleRef {
+                submesh: 1,
+                triangle: 0
+            }]
+        );
+    }
+
+    #[test]
+    fn reports_submesh_ranges() {
+        let report = ValidationReport::new("test", &test_skin(), &test_skeleton());
+
+        let misaligned = &report.submeshes[2];
+        assert!(misaligned.misaligned_offset);
+        assert!(!misaligned.range_out_of_bounds);
+        assert!(misaligned.degenerate_triangles.is_empty());
+
+        let out_of_bounds = &report.submeshes[4];
+        assert!(out_of_bounds.range_out_of_bounds);
+        assert!(!out_of_bounds.misaligned_offset);
+
+        assert_eq!(report.issue_count(), 12);
+    }
+}
@@ -2,6 +2,7 @@
 #![allow(clippy::too_many_arguments)]
 #![allow(clippy::type_complexity)]
 
+use config_json::{PlaybackMode, TimelineEnd};
 use glfw::{Action, Context, GlfwReceiver, Key};
 use std::{
     env,
@@ -19,12 +20,26 @@ mod g3d;
 mod gls;
 mod lol;
 
-use g3d::{Bones, Character, Floor, Joints, Model, Names, Refs, Shaders, Skybox};
+use g3d::{Bones, Character, Floor, Joints, Lines, Model, Names, Refs, Shaders, Skybox};
 use gls::{
     imgui_layout::{self, AddModel},
     ImguiGLFW, Screenshot, Texture,
 };
-use lol::{Animation, Skeleton, Skin};
+use lol::{
+    compare::Comparison,
+    diagnostics::AnimationReport,
+    ik::InverseKinematics,
+    layer::AnimationLayer,
+    picking::{self, MeshOutline},
+    posing::Posing,
+    rebind,
+    retarget::Retarget,
+    root_motion::RootMotion,
+    skl::joint_position,
+    skn::SkinVectors,
+    validation::ValidationReport,
+    Animation, Skeleton, Skin,
+};
 
 fn main() {
     let cargo_pkg_version = env!("CARGO_PKG_VERSION");
@@ -115,11 +130,14 @@ fn main() {
 
     let mut characters: Vec<Character> = Vec::with_capacity(config_json.paths.len());
     let mut mind_models: Vec<MindModel> = Vec::with_capacity(config_json.paths.len());
+    let mut model_uis: Vec<imgui_layout::ModelUi> = Vec::with_capacity(config_json.paths.len());
 
     for i in 0..config_json.paths.len() {
         let json_config = JsonConfig {
             meshes: &config_json.meshes[i],
             selected_animation_path: config_json.paths[i].animations.to_owned(),
+            animation_layers: &config_json.options[i].animation_layers,
+            retarget: config_json.options[i].retarget.as_ref(),
         };
 
         let add_model = AddModel {
@@ -137,6 +155,7 @@ fn main() {
             &shaders,
             &refs,
         ));
+        model_uis.push(imgui_layout::ModelUi::new());
     }
 
     let mut screenshot = Screenshot::new(use_samples, config_json.screen_shot_resolution);
@@ -195,6 +214,9 @@ fn main() {
     let mut mouse = Mouse::new(500.0f32, [0.0f32, 0.0f32]);
 
     let mut export_as = 0;
+    let mut frame_step = 0;
+    let mut gizmo_captured = false;
+    let mut mouse_dragged = false;
     let mut take_screenshot = false;
 
     let mut add_model = AddModel::new();
@@ -231,8 +253,32 @@ fn main() {
             &mut window_width,
             &mut window_height,
             &mut mouse,
+            &mut frame_step,
+            gizmo_captured,
         );
 
+        if frame_step != 0 && config_json.synchronized_time {
+            let frame_delay = mind_models
+                .iter()
+                .map(|mind_model| mind_model.animations[mind_model.animation_selected].frame_delay)
+                .fold(f32::MAX, f32::min);
+            if frame_delay != f32::MAX {
+                config_json.timeline_playing = false;
+                config_json.timeline_time = (config_json.timeline_time
+                    + frame_step as f32 * frame_delay)
+                    .clamp(0.0f32, timeline_length(&config_json, &mind_models));
+            }
+            frame_step = 0;
+        } else if frame_step != 0 {
+            for i in 0..mind_models.len() {
+                let options = &mut config_json.options[i];
+                if options.show && options.use_animation {
+                    step_animation(options, &mind_models[i], frame_step);
+                }
+            }
+            frame_step = 0;
+        }
+
         imgui_glfw.update_imgui(delta_time, &window, &mut imgui_ctx);
 
         let ui = imgui_ctx.new_frame();
@@ -265,6 +311,7 @@ fn main() {
                     ui.same_line_with_spacing(0.0f32, 3.0f32);
                     if imgui_layout::confirm_delete_button(ui) {
                         mind_models.remove(i);
+                        model_uis.remove(i);
                         config_json.paths.remove(i);
                         config_json.options.remove(i);
                         config_json.meshes.remove(i);
@@ -283,8 +330,10 @@ fn main() {
                                 ui,
                                 &mut config_json.options[i],
                                 &mut mind_models[i],
+                                &mut model_uis[i],
                                 &mut export_as,
                                 &config_json.paths[i].name,
+                                &config_json.paths[i].animations,
                             );
                         }
                     }
@@ -300,6 +349,7 @@ fn main() {
                         &shaders,
                         &refs,
                     ));
+                    model_uis.push(imgui_layout::ModelUi::new());
 
                     let name = if !add_model.name.is_empty() {
                         add_model.name.to_owned()
@@ -336,6 +386,71 @@ fn main() {
                 }
             });
 
+        for i in 0..mind_models.len() {
+            let options = &config_json.options[i];
+            if options.show && options.use_animation && options.show_dopesheet {
+                imgui_layout::dopesheet(
+                    ui,
+                    &mut config_json,
+                    i,
+                    &mut mind_models[i],
+                    &mut model_uis[i],
+                );
+            }
+            let options = &config_json.options[i];
+            if options.show && options.use_animation && options.show_curves {
+                imgui_layout::curves(
+                    ui,
+                    &mut config_json,
+                    i,
+                    &mut mind_models[i],
+                    &mut model_uis[i],
+                );
+            }
+            let options = &config_json.options[i];
+            if options.show && options.show_diagnostics {
+                update_diagnostics(&mut mind_models, i);
+                imgui_layout::diagnostics(ui, &mut config_json, i, &mind_models);
+            }
+            let options = &config_json.options[i];
+            if options.show && options.show_validation {
+                let mind_model = &mut mind_models[i];
+                if mind_model.validation.is_none() {
+                    mind_model.validation = Some(ValidationReport::new(
+                        &config_json.paths[i].name,
+                        &mind_model.skin,
+                        &mind_model.skeleton,
+                    ));
+                }
+                imgui_layout::validation(ui, &mut config_json, i, mind_model);
+            }
+            let options = &config_json.options[i];
+            if options.show && mind_models[i].show_selection {
+                imgui_layout::selection(ui, &config_json, i, &mut mind_models[i]);
+            }
+            let options = &config_json.options[i];
+            if options.show && options.show_hierarchy {
+                imgui_layout::hierarchy(
+                    ui,
+                    &mut config_json,
+                    i,
+                    &mut mind_models[i],
+                    &mut model_uis[i],
+                );
+            }
+        }
+
+        if config_json.synchronized_time {
+            let timeline_length = timeline_length(&config_json, &mind_models);
+            imgui_layout::timeline(
+                ui,
+                &mut config_json,
+                &mind_models,
+                timeline_length,
+                window_height as f32,
+            );
+        }
+
         if take_screenshot {
             screenshot.update();
         }
@@ -367,9 +482,29 @@ fn main() {
             projection_view_matrix
         };
 
+        if config_json.synchronized_time {
+            advance_timeline(&mut config_json, &mind_models, delta_time);
+        }
+
+        gizmo_captured = false;
+
+        if ui.is_mouse_clicked(imgui::MouseButton::Left) {
+            mouse_dragged = false;
+        }
+        mouse_dragged |= ui.is_mouse_dragging_with_threshold(imgui::MouseButton::Left, 3.0f32);
+        let pick_position = (ui.is_mouse_released(imgui::MouseButton::Left)
+            && !mouse_dragged
+            && imgui_layout::no_window_hovered())
+        .then(|| glam::Vec2::from_array(ui.io().mouse_pos));
+        let mut pick_hit: Option<(usize, Pick, f32)> = None;
+
         for i in 0..mind_models.len() {
-            let animation_synchronized_time = if config_json.synchronized_time && i != 0 {
-                Some(config_json.options[0].animation_time)
+            let animation_synchronized_time = if config_json.synchronized_time {
+                Some(timeline_time(
+                    config_json.timeline_time,
+                    &config_json.options[i],
+                    &mind_models[i].animations[mind_models[i].animation_selected],
+                ))
             } else {
                 None
             };
@@ -382,36 +517,257 @@ fn main() {
 
                 play_animation(options, mind_model, delta_time, animation_synchronized_time);
 
-                character
-                    .model
-                    .render(options, &projection_view_matrix_mod, mind_model);
+                if mind_model.posing.enabled && !options.use_animation {
+                    mind_model
+                        .posing
+                        .apply_bind_pose(&mut mind_model.joints_transforms, &mind_model.skeleton);
+                }
+                let skinned = options.use_animation || mind_model.posing.enabled;
+
+                if mind_model.rebind_pose {
+                    mind_model.rebind_pose = false;
+                    if skinned {
+                        rebind_mind_model(mind_model, character, &shaders, &refs);
+                    } else {
+                        println!("Nothing to rebind, the model is in its bind pose");
+                    }
+                }
+
+                let model_matrix = if options.use_animation {
+                    mind_model.root_motion.offset
+                } else {
+                    glam::Mat4::IDENTITY
+                };
+                let model_projection_view_matrix = projection_view_matrix_mod * model_matrix;
+
+                if options.use_animation && options.show_root_path {
+                    mind_model.root_motion.update_path(
+                        &mind_model.animations[mind_model.animation_selected],
+                        mind_model.animation_selected,
+                        &mind_model.skeleton,
+                    );
+
+                    let path_count = mind_model.root_motion.path.len();
+                    let path_positions: Vec<glam::Vec4> = mind_model
+                        .root_motion
+                        .path
+                        .iter()
+                        .map(|position| position.extend(1.0f32))
+                        .collect();
+                    let path_colors: Vec<glam::Vec3> = (0..path_count)
+                        .map(|j| {
+                            let lerp_value = j as f32 / (path_count.max(2) - 1) as f32;
+                            glam::vec3(1.0f32, 1.0f32, 0.0f32)
+                                .lerp(glam::vec3(1.0f32, 0.0f32, 0.0f32), lerp_value)
+                        })
+                        .collect();
+
+                    character.root_path.update(&path_positions, &path_colors);
+                    character.root_path.render(
+                        gl::LINE_STRIP,
+                        3.0f32,
+                        true,
+                        &(projection_view_matrix_mod * mind_model.root_motion.offset),
+                    );
+                }
+
+                character.model.render(
+                    options.show_wireframe,
+                    skinned,
+                    options.render_mode,
+                    options.heatmap_mode,
+                    &config_json.lighting,
+                    model_matrix
+                        .inverse()
+                        .transform_vector3(config_json.lighting.direction())
+                        .normalize_or_zero(),
+                    model_matrix.inverse().transform_point3(camera_pos),
+                    &(view_matrix * model_matrix),
+                    &model_projection_view_matrix,
+                    mind_model,
+                );
+
+                if options.wireframe_overlay.show || options.wireframe_overlay.show_vertices {
+                    character.model.render_overlay(
+                        skinned,
+                        use_samples,
+                        &options.wireframe_overlay,
+                        &model_projection_view_matrix,
+                        mind_model,
+                    );
+                }
+
+                if options.use_animation {
+                    update_comparison(options, mind_model);
+                    if let Some(comparison) = &mind_model.comparison {
+                        if comparison.show_ghost {
+                            character.model.render_ghost(
+                                use_samples,
+                                &model_projection_view_matrix,
+                                &comparison.joints_transforms,
+                                mind_model,
+                                &comparison.ghost_color,
+                            );
+                        }
+                    }
+                }
+
+                if options.use_animation && options.onion_skin.show {
+                    update_onion_skin(options, mind_model);
+                    let frames = mind_model
+                        .onion_skin
+                        .as_ref()
+                        .map_or(&[][..], |onion_skin| &onion_skin.frames);
+                    for (joints_transforms, color) in frames {
+                        if options.onion_skin.bones_only {
+                            character.bones.render_ghost(
+                                use_samples,
+                                &model_projection_view_matrix,
+                                joints_transforms,
+                                mind_model,
+                                color,
+                            );
+                        } else {
+                            character.model.render_ghost(
+                                use_samples,
+                                &model_projection_view_matrix,
+                                joints_transforms,
+                                mind_model,
+                                color,
+                            );
+                        }
+                    }
+                }
+
+                if options.use_animation && options.motion_trails.show {
+                    render_motion_trails(
+                        &mut character.trails,
+                        options,
+                        &model_projection_view_matrix,
+                        mind_model,
+                    );
+                }
 
                 if options.show_skeleton_bones {
-                    character.bones.render(
-                        options.use_animation,
-                        &projection_view_matrix_mod,
+                    character
+                        .bones
+                        .render(skinned, &model_projection_view_matrix, mind_model);
+                }
+
+                let vertex_vectors = &options.vertex_vectors;
+                if vertex_vectors.show_normals || vertex_vectors.show_tangents {
+                    if mind_model.skin_vectors.is_none() {
+                        mind_model.skin_vectors = Some(SkinVectors::new(&mind_model.skin));
+                    }
+                    render_vertex_vectors(
+                        &mut character.vectors,
+                        vertex_vectors,
+                        skinned,
+                        &model_projection_view_matrix,
+                        mind_model,
+                    );
+                }
+
+                if options.joint_axes.show {
+                    render_joint_axes(
+                        &mut character.axes,
+                        &options.joint_axes,
+                        skinned,
+                        &model_projection_view_matrix,
                         mind_model,
                     );
                 }
 
                 if options.show_skeleton_names {
                     character.names.render(
-                        options.use_animation,
-                        &camera_pos,
-                        &view_matrix,
-                        &projection_view_matrix_mod,
+                        skinned,
+                        &model_matrix.inverse().transform_point3(camera_pos),
+                        &(view_matrix * model_matrix),
+                        &model_projection_view_matrix,
                         mind_model,
                     );
                 }
 
                 if options.show_skeleton_joints {
                     character.joints.render(
-                        options.use_animation,
+                        skinned,
                         use_samples,
-                        &projection_view_matrix_mod,
+                        &model_projection_view_matrix,
+                        mind_model,
+                    );
+                }
+
+                if let Some(selected_mesh) = mind_model.selected_mesh {
+                    render_mesh_outline(
+                        &mut character.outline,
+                        selected_mesh,
+                        skinned,
+                        model_matrix.inverse().transform_point3(camera_pos),
+                        &model_projection_view_matrix,
+                        mind_model,
+                    );
+                }
+
+                if let Some(selected_joint) = mind_model.selected_joint {
+                    render_joint_highlight(
+                        &mut character.highlight,
+                        selected_joint,
+                        skinned,
+                        &model_projection_view_matrix,
                         mind_model,
                     );
                 }
+
+                if mind_model.posing.enabled {
+                    gizmo_captured |= imgui_layout::pose_gizmo(
+                        ui,
+                        mind_model,
+                        &mut model_uis[i],
+                        &model_projection_view_matrix,
+                    );
+                }
+
+                if options.use_animation && mind_model.ik.is_active() {
+                    gizmo_captured |=
+                        imgui_layout::ik_gizmo(ui, mind_model, &model_projection_view_matrix);
+                }
+
+                if let Some(pick_position) = pick_position {
+                    if let Some((pick, depth)) = pick_model(
+                        pick_position,
+                        ui.io().display_size,
+                        options,
+                        skinned,
+                        &model_projection_view_matrix,
+                        mind_model,
+                    ) {
+                        if !matches!(pick_hit, Some((_, _, hit_depth)) if hit_depth <= depth) {
+                            pick_hit = Some((i, pick, depth));
+                        }
+                    }
+                }
+            }
+        }
+
+        if pick_position.is_some() && !gizmo_captured {
+            for mind_model in mind_models.iter_mut() {
+                mind_model.selected_mesh = None;
+            }
+            match pick_hit {
+                Some((i, Pick::Joint(joint), _)) => {
+                    mind_models[i].selected_joint = Some(joint);
+                    mind_models[i].show_selection = true;
+                }
+                Some((i, Pick::Mesh(mesh), _)) => {
+                    mind_models[i].selected_mesh = Some(mesh);
+                    mind_models[i].show_selection = true;
+                }
+                None => {
+                    for mind_model in mind_models.iter_mut() {
+                        mind_model.selected_joint = None;
+                        mind_model.show_selection = false;
+                    }
+                }
             }
         }
 
@@ -433,6 +789,8 @@ fn main() {
 pub struct JsonConfig<'a> {
     meshes: &'a [config_json::MeshJson],
     selected_animation_path: String,
+    animation_layers: &'a [config_json::LayerJson],
+    retarget: Option<&'a config_json::RetargetJson>,
 }
 
 pub struct MindModel {
@@ -450,6 +808,59 @@ pub struct MindModel {
     pub animations: Vec<Animation>,
     pub animation_selected: usize,
     pub animations_file_names: Vec<String>,
+    pub animation_layers: Vec<AnimationLayer>,
+
+    pub retarget: Option<Retarget>,
+
+    pub root_motion: RootMotion,
+
+    pub selected_joint: Option<usize>,
+
+    pub diagnostics: Option<AnimationReport>,
+    pub validation: Option<ValidationReport>,
+
+    pub comparison: Option<Comparison>,
+
+    pub posing: Posing,
+    pub rebind_pose: bool,
+
+    pub ik: InverseKinematics,
+    pub pose_revision: u64,
+
+    pub onion_skin: Option<OnionSkin>,
+    pub motion_trail: Option<MotionTrail>,
+
+    pub selected_mesh: Option<usize>,
+    pub mesh_outline: Option<MeshOutline>,
+    pub skin_vectors: Option<SkinVectors>,
+    pub vertex_vector_lines: Option<VertexVectorLines>,
+    pub show_selection: bool,
+}
+
+pub struct VertexVectorLines {
+    pub joints_transforms: Vec<glam::Mat4>,
+    pub settings: config_json::VertexVectorsJson,
+    pub show_meshes: Vec<bool>,
+    pub positions: Vec<glam::Vec4>,
+    pub colors: Vec<glam::Vec3>,
+}
+
+pub struct OnionSkin {
+    pub animation: usize,
+    pub time: f32,
+    pub pose_revision: u64,
+    pub settings: config_json::OnionSkinJson,
+    pub lock_root_motion: bool,
+    pub frames: Vec<(Vec<glam::Mat4>, [f32; 4])>,
+}
+
+pub struct MotionTrail {
+    pub animation: usize,
+    pub pose_revision: u64,
+    pub range: (f32, f32),
+    pub joints: Vec<usize>,
+    pub lock_root_motion: bool,
+    pub samples: Vec<Vec<(f32, glam::Vec3)>>,
 }
 
 fn load_mind_model(
@@ -530,6 +941,38 @@ fn load_mind_model(
         animations_file_names.push(String::from(path.file_stem().unwrap().to_str().unwrap()));
     }
 
+    let mut retarget = None;
+    if let Some(retarget_json) = config_json.as_ref().and_then(|x| x.retarget) {
+        if let Some(mut new_retarget) = load_retarget(
+            &retarget_json.source_skeleton,
+            &retarget_json.source_animations,
+            &skeleton,
+        ) {
+            new_retarget.clear();
+            for (target_name, source_name) in retarget_json.mapping.iter() {
+                let target_position = skeleton
+                    .joints
+                    .iter()
+                    .position(|joint| joint.name == *target_name);
+                let source_position = new_retarget
+                    .skeleton
+                    .joints
+                    .iter()
+                    .position(|joint| joint.name == *source_name);
+                if let Some(target_position) = target_position {
+                    new_retarget.mapping[target_position] = source_position;
+                }
+            }
+            retarget_animations(
+                &mut new_retarget,
+                &skeleton,
+                &mut animations,
+                &mut animations_file_names,
+            );
+            retarget = Some(new_retarget);
+        }
+    }
+
     let mut animation_selected = 0;
     if let Some(config_json) = &config_json {
         if let Some(animation_position) = animations_file_names
@@ -540,23 +983,65 @@ fn load_mind_model(
         }
     }
 
+    let mut animation_layers = vec![];
+    if let Some(config_json) = &config_json {
+        for layer_json in config_json.animation_layers {
+            if let Some(animation_position) = animations_file_names
+                .iter()
+                .position(|x| *x == layer_json.animation)
+            {
+                let mut layer = AnimationLayer::new(animation_position);
+                layer.weight = layer_json.weight;
+                layer.additive = layer_json.additive;
+                layer.mask = layer_json.mask_joint.as_ref().and_then(|mask_joint| {
+                    skeleton
+                        .joints
+                        .iter()
+                        .position(|joint| joint.name == *mask_joint)
+                });
+                animation_layers.push(layer);
+            }
+        }
+    }
+
     let mut model = Model::create(&skin, Rc::clone(&shaders.model));
     let mut names = Names::create(&skeleton, Rc::clone(&shaders.names));
     let mut bones = Bones::create(&skeleton, Rc::clone(&shaders.bones));
     let mut joints = Joints::create(&skeleton, Rc::clone(&shaders.joints));
+    let mut root_path = Lines::create(Rc::clone(&shaders.lines));
+    let mut highlight = Lines::create(Rc::clone(&shaders.lines));
+    let mut trails = Lines::create(Rc::clone(&shaders.lines));
+    let mut axes = Lines::create(Rc::clone(&shaders.lines));
+    let mut outline = Lines::create(Rc::clone(&shaders.lines));
+    let mut vectors = Lines::create(Rc::clone(&shaders.lines));
 
     model.set_shader_refs(&refs.model, refs.model_ubo);
     names.set_shader_refs(&refs.names);
     bones.set_shader_refs(&refs.bones);
     joints.set_shader_refs(&refs.joints);
+    root_path.set_shader_refs(&refs.lines);
+    highlight.set_shader_refs(&refs.lines);
+    trails.set_shader_refs(&refs.lines);
+    axes.set_shader_refs(&refs.lines);
+    outline.set_shader_refs(&refs.lines);
+    vectors.set_shader_refs(&refs.lines);
 
     characters.push(Character {
         bones,
         joints,
         model,
         names,
+        root_path,
+        highlight,
+        trails,
+        axes,
+        outline,
+        vectors,
     });
 
+    let root_motion = RootMotion::new(&skeleton);
+    let posing = Posing::new(&skeleton);
+
     MindModel {
         skin,
         skeleton,
@@ -569,6 +1054,220 @@ fn load_mind_model(
         animations,
         animation_selected,
         animations_file_names,
+        animation_layers,
+        retarget,
+        root_motion,
+        selected_joint: None,
+        diagnostics: None,
+        validation: None,
+        comparison: None,
+        posing,
+        rebind_pose: false,
+        ik: InverseKinematics::new(),
+        pose_revision: 0,
+        onion_skin: None,
+        motion_trail: None,
+        selected_mesh: None,
+        mesh_outline: None,
+        skin_vectors: None,
+        vertex_vector_lines: None,
+        show_selection: false,
+    }
+}
+
+fn rebind_mind_model(
+    mind_model: &mut MindModel,
+    character: &mut Character,
+    shaders: &Shaders,
+    refs: &Refs,
+) {
+    let skin = rebind::bake_skin(&mind_model.skin, &mind_model.joints_transforms);
+    let skeleton = rebind::rebind_skeleton(&mind_model.skeleton, &mind_model.joints_transforms);
+
+    character.model = Model::create(&skin, Rc::clone(&shaders.model));
+    character.names = Names::create(&skeleton, Rc::clone(&shaders.names));
+    character.bones = Bones::create(&skeleton, Rc::clone(&shaders.bones));
+    character.joints = Joints::create(&skeleton, Rc::clone(&shaders.joints));
+
+    character.model.set_shader_refs(&refs.model, refs.model_ubo);
+    character.names.set_shader_refs(&refs.names);
+    character.bones.set_shader_refs(&refs.bones);
+    character.joints.set_shader_refs(&refs.joints);
+
+    mind_model.root_motion = RootMotion::new(&skeleton);
+    mind_model.posing.reset();
+    mind_model.posing.enabled = false;
+    mind_model.joints_transforms.fill(glam::Mat4::IDENTITY);
+    mind_model.mesh_outline = None;
+    mind_model.skin_vectors = None;
+    mind_model.vertex_vector_lines = None;
+    mind_model.validation = None;
+    mind_model.onion_skin = None;
+    mind_model.motion_trail = None;
+    mind_model.diagnostics = None;
+    mind_model.comparison = None;
+    mind_model.pose_revision += 1;
+
+    mind_model.skin = skin;
+    mind_model.skeleton = skeleton;
+
+    // Retargeted clips were baked against the old bind pose
+    update_retarget(mind_model);
+
+    println!("Skeleton was rebound to the current pose");
+}
+
+fn load_retarget(
+    skeleton_path: &str,
+    animations_path: &str,
+    target: &Skeleton,
+) -> Option<Retarget> {
+    if !Path::new(skeleton_path).exists() {
+        println!("Skipping retarget, source skeleton not found: {skeleton_path}");
+        return None;
+    }
+
+    let skeleton = Skeleton::read(&read_to_u8(Path::new(skeleton_path)));
+    Some(Retarget::new(
+        skeleton,
+        skeleton_path.to_owned(),
+        animations_path.to_owned(),
+        target,
+    ))
+}
+
+fn retarget_animations(
+    retarget: &mut Retarget,
+    target: &Skeleton,
+    animations: &mut Vec<Animation>,
+    animations_file_names: &mut Vec<String>,
+) {
+    let original_count = animations.len() - retarget.animation_count;
+    animations.truncate(original_count);
+    animations_file_names.truncate(original_count);
+
+    let animations_paths = glob::glob(format!("{}/*.anm", retarget.animations_path).as_str())
+        .expect("Failed to read glob anm pattern")
+        .filter_map(Result::ok);
+
+    for path in animations_paths {
+        let animation = Animation::read(&read_to_u8(&path));
+        animations.push(retarget.retarget_animation(&animation, target));
+        animations_file_names.push(format!(
+            "retarget_{}",
+            path.file_stem().unwrap().to_str().unwrap()
+        ));
+    }
+
+    retarget.animation_count = animations.len() - original_count;
+}
+
+fn update_retarget(mind_model: &mut MindModel) {
+    if let Some(retarget) = &mut mind_model.retarget {
+        retarget_animations(
+            retarget,
+            &mind_model.skeleton,
+            &mut mind_model.animations,
+            &mut mind_model.animations_file_names,
+        );
+        clamp_animation_selected(mind_model);
+    }
+}
+
+fn set_retarget(mind_model: &mut MindModel, skeleton_path: &str, animations_path: &str) {
+    remove_retarget(mind_model);
+    mind_model.retarget = load_retarget(skeleton_path, animations_path, &mind_model.skeleton);
+}
+
+fn remove_retarget(mind_model: &mut MindModel) {
+    if let Some(retarget) = mind_model.retarget.take() {
+        let original_count = mind_model.animations.len() - retarget.animation_count;
+        mind_model.animations.truncate(original_count);
+        mind_model.animations_file_names.truncate(original_count);
+        clamp_animation_selected(mind_model);
+    }
+}
+
+fn add_animation(mind_model: &mut MindModel, animation: Animation, name: String) -> usize {
+    let position = mind_model.animations.len()
+        - mind_model
+            .retarget
+            .as_ref()
+            .map_or(0, |retarget| retarget.animation_count);
+
+    mind_model.animations.insert(position, animation);
+    mind_model.animations_file_names.insert(position, name);
+
+    if mind_model.animation_selected >= position {
+        mind_model.animation_selected += 1;
+    }
+    for layer in mind_model.animation_layers.iter_mut() {
+        if layer.animation >= position {
+            layer.animation += 1;
+        }
+    }
+    mind_model.root_motion.path_animation = None;
+    mind_model.onion_skin = None;
+    mind_model.motion_trail = None;
+    mind_model.diagnostics = None;
+    if let Some(comparison) = &mut mind_model.comparison {
+        comparison.compared_animation = None;
+    }
+
+    position
+}
+
+fn clamp_animation_selected(mind_model: &mut MindModel) {
+    let animations_count = mind_model.animations.len();
+    if mind_model.animation_selected >= animations_count {
+        mind_model.animation_selected = 0;
+    }
+    mind_model
+        .animation_layers
+        .retain(|layer| layer.animation < animations_count);
+    mind_model.root_motion.path_animation = None;
+    mind_model.onion_skin = None;
+    mind_model.motion_trail = None;
+    mind_model.diagnostics = None;
+    if let Some(comparison) = &mut mind_model.comparison {
+        comparison.compared_animation = None;
+    }
+}
+
+fn animation_report(
+    mind_models: &[MindModel],
+    index: usize,
+    animation_index: usize,
+) -> AnimationReport {
+    let mind_model = &mind_models[index];
+    let name_sources: Vec<&Skeleton> = mind_models
+        .iter()
+        .map(|mind_model| &mind_model.skeleton)
+        .chain(
+            mind_model
+                .retarget
+                .as_ref()
+                .map(|retarget| &retarget.skeleton),
+        )
+        .collect();
+
+    AnimationReport::new(
+        &mind_model.animations[animation_index],
+        animation_index,
+        &mind_model.animations_file_names[animation_index],
+        &mind_model.skeleton,
+        &name_sources,
+    )
+}
+
+fn update_diagnostics(mind_models: &mut [MindModel], index: usize) {
+    let animation_selected = mind_models[index].animation_selected;
+    if !matches!(
+        &mind_models[index].diagnostics,
+        Some(report) if report.animation == animation_selected
+    ) {
+        mind_models[index].diagnostics =
+            Some(animation_report(mind_models, index, animation_selected));
     }
 }
 
@@ -600,6 +1299,8 @@ fn process_events(
     window_width: &mut i32,
     window_height: &mut i32,
     mouse: &mut Mouse,
+    frame_step: &mut i32,
+    gizmo_captured: bool,
 ) {
     for (_, event) in glfw::flush_messages(events) {
         imgui_glfw.handle_event(imgui_ctx, &event);
@@ -612,10 +1313,19 @@ fn process_events(
             glfw::WindowEvent::Key(Key::Escape, _, Action::Press, _) => {
                 window.set_should_close(true)
             }
+            glfw::WindowEvent::Key(
+                key @ (Key::Left | Key::Right),
+                _,
+                Action::Press | Action::Repeat,
+                _,
+            ) if !imgui_ctx.io().want_capture_keyboard => {
+                *frame_step += if key == Key::Left { -1 } else { 1 };
+            }
             glfw::WindowEvent::Close => window.set_should_close(true),
             glfw::WindowEvent::MouseButton(button, action, _) => {
                 if (action == Action::Press || action == Action::Repeat)
                     && imgui_layout::no_window_hovered()
+                    && !gizmo_captured
                 {
                     if button == glfw::MouseButtonLeft {
                         mouse.state = 1;
@@ -708,19 +1418,46 @@ fn play_animation(
     animation_synchronized_time: Option<f32>,
 ) {
     if options.use_animation {
-        if options.play_animation {
-            if options.animation_time
-                < mind_model.animations[mind_model.animation_selected].duration
-            {
-                options.animation_time += delta_time * options.animation_speed;
+        if options.play_animation && animation_synchronized_time.is_none() {
+            let (range_start, range_end) = animation_range(
+                options,
+                &mind_model.animations[mind_model.animation_selected],
+            );
+
+            let reverse = match options.playback_mode {
+                PlaybackMode::Forward => false,
+                PlaybackMode::Reverse => true,
+                PlaybackMode::PingPong => options.ping_pong_reverse,
+            };
+
+            let animation_step = delta_time * options.animation_speed;
+
+            if !reverse && options.animation_time < range_end {
+                options.animation_time = (options.animation_time + animation_step).min(range_end);
+            } else if reverse && options.animation_time > range_start {
+                options.animation_time = (options.animation_time - animation_step).max(range_start);
+            } else if options.playback_mode == PlaybackMode::PingPong && !reverse {
+                options.ping_pong_reverse = true;
             } else if options.next_animation {
+                accumulate_root_motion(options, mind_model, range_start, range_end);
                 mind_model.animation_selected += 1;
                 if mind_model.animation_selected == mind_model.animations.len() {
                     mind_model.animation_selected = 0;
                 }
-                options.animation_time = 0.0f32;
+                options.ping_pong_reverse = false;
+                options.animation_time = match options.playback_mode {
+                    PlaybackMode::Reverse => {
+                        mind_model.animations[mind_model.animation_selected].duration
+                    }
+                    _ => 0.0f32,
+                };
             } else if options.loop_animation {
-                options.animation_time = 0.0f32;
+                accumulate_root_motion(options, mind_model, range_start, range_end);
+                match options.playback_mode {
+                    PlaybackMode::Forward => options.animation_time = range_start,
+                    PlaybackMode::Reverse => options.animation_time = range_end,
+                    PlaybackMode::PingPong => options.ping_pong_reverse = false,
+                }
             }
         }
         if let Some(animation_time) = animation_synchronized_time {
@@ -728,11 +1465,702 @@ fn play_animation(
         }
         lol::anm::run_animation(
             &mut mind_model.joints_transforms,
+            &mind_model.animations,
+            mind_model.animation_selected,
+            &mind_model.animation_layers,
+            &mind_model.skeleton,
+            evaluated_time(
+                options,
+                &mind_model.animations[mind_model.animation_selected],
+            ),
+            mind_model
+                .root_motion
+                .root
+                .filter(|_| options.lock_root_motion),
+            Some(&mind_model.posing).filter(|posing| posing.enabled),
+            Some(&mind_model.ik).filter(|ik| ik.is_active()),
+        );
+    }
+}
+
+fn update_comparison(options: &config_json::OptionsJson, mind_model: &mut MindModel) {
+    let Some(comparison) = &mut mind_model.comparison else {
+        return;
+    };
+
+    let animation = &mind_model.animations[mind_model.animation_selected];
+    if comparison.compared_animation != Some(mind_model.animation_selected) {
+        comparison.compare(
+            animation,
+            mind_model.animation_selected,
+            &mind_model.skeleton,
+        );
+    }
+
+    if comparison.show_ghost {
+        lol::anm::run_animation(
+            &mut comparison.joints_transforms,
+            std::slice::from_ref(&comparison.animation),
+            0,
+            &[],
+            &mind_model.skeleton,
+            evaluated_time(options, animation).min(comparison.animation.duration),
+            mind_model
+                .root_motion
+                .root
+                .filter(|_| options.lock_root_motion),
+            None,
+            None,
+        );
+    }
+}
+
+fn update_onion_skin(options: &config_json::OptionsJson, mind_model: &mut MindModel) {
+    let onion_skin = &options.onion_skin;
+    let animation = &mind_model.animations[mind_model.animation_selected];
+    let time = evaluated_time(options, animation);
+
+    let cached = mind_model.onion_skin.as_ref().is_some_and(|cached| {
+        cached.animation == mind_model.animation_selected
+            && cached.time == time
+            && cached.pose_revision == mind_model.pose_revision
+            && cached.settings == *onion_skin
+            && cached.lock_root_motion == options.lock_root_motion
+    });
+    if cached {
+        return;
+    }
+
+    let step = animation.frame_delay * onion_skin.step.max(1) as f32;
+    let root_offset = locked_root_delta(options, mind_model, time).inverse();
+
+    let mut frames: Vec<(Vec<glam::Mat4>, [f32; 4])> = Vec::with_capacity(onion_skin.frames * 2);
+    for i in 1..=onion_skin.frames {
+        let fade = 1.0f32 - (i - 1) as f32 / onion_skin.frames as f32;
+        for (direction, color) in [
+            (-1.0f32, onion_skin.before_color),
+            (1.0f32, onion_skin.after_color),
+        ] {
+            let frame_time = time + direction * step * i as f32;
+            if !(0.0f32..=animation.duration).contains(&frame_time) {
+                continue;
+            }
+
+            let mut joints_transforms =
+                vec![glam::Mat4::IDENTITY; mind_model.skeleton.joints.len()];
+            let root_delta = root_offset
+                * run_animation_at(options, mind_model, &mut joints_transforms, frame_time);
+            for joint_transform in joints_transforms.iter_mut() {
+                *joint_transform = root_delta * *joint_transform;
+            }
+            frames.push((
+                joints_transforms,
+                [color[0], color[1], color[2], color[3] * fade],
+            ));
+        }
+    }
+    frames.reverse();
+
+    mind_model.onion_skin = Some(OnionSkin {
+        animation: mind_model.animation_selected,
+        time,
+        pose_revision: mind_model.pose_revision,
+        settings: onion_skin.clone(),
+        lock_root_motion: options.lock_root_motion,
+        frames,
+    });
+}
+
+const TRAIL_COLORS: [glam::Vec3; 6] = [
+    glam::Vec3::new(1.0f32, 0.4f32, 0.2f32),
+    glam::Vec3::new(0.2f32, 0.8f32, 1.0f32),
+    glam::Vec3::new(0.9f32, 0.9f32, 0.2f32),
+    glam::Vec3::new(0.9f32, 0.3f32, 0.9f32),
+    glam::Vec3::new(0.3f32, 1.0f32, 0.4f32),
+    glam::Vec3::new(1.0f32, 1.0f32, 1.0f32),
+];
+
+fn render_motion_trails(
+    trails: &mut Lines,
+    options: &config_json::OptionsJson,
+    projection_view_matrix: &glam::Mat4,
+    mind_model: &mut MindModel,
+) {
+    let motion_trails = &options.motion_trails;
+    let skeleton = &mind_model.skeleton;
+    let joints: Vec<usize> = motion_trails
+        .joints
+        .iter()
+        .filter_map(|name| skeleton.joints.iter().position(|joint| joint.name == *name))
+        .collect();
+
+    let animation = &mind_model.animations[mind_model.animation_selected];
+    if joints.is_empty() || animation.frame_delay <= 0.0f32 {
+        return;
+    }
+
+    let time = evaluated_time(options, animation);
+    let range = if motion_trails.whole_clip {
+        (0.0f32, animation.duration)
+    } else {
+        (
+            (time - motion_trails.window).max(0.0f32),
+            (time + motion_trails.window).min(animation.duration),
+        )
+    };
+
+    let cached = mind_model.motion_trail.as_ref().is_some_and(|trail| {
+        trail.animation == mind_model.animation_selected
+            && trail.pose_revision == mind_model.pose_revision
+            && trail.range == range
+            && trail.joints == joints
+            && trail.lock_root_motion == options.lock_root_motion
+    });
+    if !cached {
+        mind_model.motion_trail = Some(sample_motion_trail(options, mind_model, joints, range));
+    }
+    let Some(trail) = &mind_model.motion_trail else {
+        return;
+    };
+
+    let animation = &mind_model.animations[mind_model.animation_selected];
+    let span = (range.1 - range.0).max(animation.frame_delay);
+    let root_offset = locked_root_delta(options, mind_model, time).inverse();
+
+    let sample_count: usize = trail.samples.iter().map(|samples| samples.len()).sum();
+    let mut positions: Vec<glam::Vec4> = Vec::with_capacity(sample_count * 2);
+    let mut colors: Vec<glam::Vec3> = Vec::with_capacity(sample_count * 2);
+    for (j, joint_samples) in trail.samples.iter().enumerate() {
+        let color = TRAIL_COLORS[j % TRAIL_COLORS.len()];
+        for segment in joint_samples.windows(2) {
+            for (sample_time, position) in segment.iter() {
+                let distance = ((sample_time - time).abs() / span).min(1.0f32);
+                let sample_color = if *sample_time <= time {
+                    color * (1.0f32 - distance * 0.8f32)
+                } else {
+                    color.lerp(glam::Vec3::ONE, 0.5f32) * (1.0f32 - distance * 0.8f32)
+                };
+                positions.push(root_offset.transform_point3(*position).extend(1.0f32));
+                colors.push(sample_color);
+            }
+        }
+    }
+
+    trails.update(&positions, &colors);
+    trails.render(
+        gl::LINES,
+        motion_trails.width,
+        false,
+        projection_view_matrix,
+    );
+}
+
+fn sample_motion_trail(
+    options: &config_json::OptionsJson,
+    mind_model: &MindModel,
+    joints: Vec<usize>,
+    range: (f32, f32),
+) -> MotionTrail {
+    let skeleton = &mind_model.skeleton;
+    let animation = &mind_model.animations[mind_model.animation_selected];
+    let (start, end) = range;
+    let sample_count = ((end - start) / animation.frame_delay).round() as usize + 1;
+
+    let mut samples: Vec<Vec<(f32, glam::Vec3)>> =
+        vec![Vec::with_capacity(sample_count); joints.len()];
+    let mut joints_transforms = vec![glam::Mat4::IDENTITY; skeleton.joints.len()];
+    for i in 0..sample_count {
+        let sample_time = (start + i as f32 * animation.frame_delay).min(end);
+        let root_delta = run_animation_at(options, mind_model, &mut joints_transforms, sample_time);
+        for (j, joint) in joints.iter().enumerate() {
+            samples[j].push((
+                sample_time,
+                root_delta.transform_point3(joint_position(
+                    &(joints_transforms[*joint] * skeleton.joints[*joint].global_matrix),
+                )),
+            ));
+        }
+    }
+
+    MotionTrail {
+        animation: mind_model.animation_selected,
+        pose_revision: mind_model.pose_revision,
+        range,
+        joints,
+        lock_root_motion: options.lock_root_motion,
+        samples,
+    }
+}
+
+fn run_animation_at(
+    options: &config_json::OptionsJson,
+    mind_model: &MindModel,
+    joints_transforms: &mut [glam::Mat4],
+    time: f32,
+) -> glam::Mat4 {
+    lol::anm::run_animation(
+        joints_transforms,
+        &mind_model.animations,
+        mind_model.animation_selected,
+        &mind_model.animation_layers,
+        &mind_model.skeleton,
+        time,
+        mind_model
+            .root_motion
+            .root
+            .filter(|_| options.lock_root_motion),
+        Some(&mind_model.posing).filter(|posing| posing.enabled),
+        Some(&mind_model.ik).filter(|ik| ik.is_active()),
+    );
+    locked_root_delta(options, mind_model, time)
+}
+
+fn locked_root_delta(
+    options: &config_json::OptionsJson,
+    mind_model: &MindModel,
+    time: f32,
+) -> glam::Mat4 {
+    match mind_model
+        .root_motion
+        .root
+        .filter(|_| options.lock_root_motion)
+    {
+        Some(root) => lol::root_motion::root_delta(
             &mind_model.animations[mind_model.animation_selected],
             &mind_model.skeleton,
-            options.animation_time,
+            root,
+            time,
+        ),
+        None => glam::Mat4::IDENTITY,
+    }
+}
+
+fn render_joint_axes(
+    axes: &mut Lines,
+    joint_axes: &config_json::JointAxesJson,
+    use_animation: bool,
+    projection_view_matrix: &glam::Mat4,
+    mind_model: &MindModel,
+) {
+    let skeleton = &mind_model.skeleton;
+    let filter = joint_axes.filter.to_lowercase();
+    let subtree = mind_model
+        .selected_joint
+        .filter(|_| joint_axes.selected_subtree)
+        .map(|selected_joint| skeleton.subtree(selected_joint));
+
+    let mut positions: Vec<glam::Vec4> = Vec::with_capacity(skeleton.joints.len() * 6);
+    let mut colors: Vec<glam::Vec3> = Vec::with_capacity(skeleton.joints.len() * 6);
+    for (i, joint) in skeleton.joints.iter().enumerate() {
+        if !filter.is_empty() && !joint.name.to_lowercase().contains(&filter) {
+            continue;
+        }
+        if matches!(&subtree, Some(subtree) if !subtree.contains(&i)) {
+            continue;
+        }
+
+        let global_matrix = if use_animation && !joint_axes.bind_pose {
+            mind_model.joints_transforms[i] * joint.global_matrix
+        } else {
+            joint.global_matrix
+        };
+        let origin = joint_position(&global_matrix).extend(1.0f32);
+
+        for (axis, color) in [
+            (global_matrix.x_axis, glam::Vec3::X),
+            (global_matrix.y_axis, glam::Vec3::Y),
+            (global_matrix.z_axis, glam::Vec3::Z),
+        ] {
+            let direction = axis.truncate().normalize_or_zero() * joint_axes.size;
+            positions.push(origin);
+            positions.push(origin + direction.extend(0.0f32));
+            colors.push(color);
+            colors.push(color);
+        }
+    }
+
+    axes.update(&positions, &colors);
+    axes.render(gl::LINES, 2.0f32, false, projection_view_matrix);
+}
+
+const NORMAL_COLOR: glam::Vec3 = glam::Vec3::new(0.2f32, 0.6f32, 1.0f32);
+const TANGENT_COLOR: glam::Vec3 = glam::Vec3::new(1.0f32, 0.4f32, 0.2f32);
+const INVALID_NORMAL_COLOR: glam::Vec3 = glam::Vec3::new(1.0f32, 0.0f32, 1.0f32);
+const FLIPPED_NORMAL_COLOR: glam::Vec3 = glam::Vec3::new(1.0f32, 1.0f32, 0.0f32);
+
+fn render_vertex_vectors(
+    vectors: &mut Lines,
+    vertex_vectors: &config_json::VertexVectorsJson,
+    use_animation: bool,
+    projection_view_matrix: &glam::Mat4,
+    mind_model: &mut MindModel,
+) {
+    let joints_transforms: &[glam::Mat4] = if use_animation {
+        &mind_model.joints_transforms
+    } else {
+        &[]
+    };
+    let cached = mind_model
+        .vertex_vector_lines
+        .as_ref()
+        .is_some_and(|lines| {
+            lines.joints_transforms == joints_transforms
+                && lines.settings == *vertex_vectors
+                && lines.show_meshes == mind_model.show_meshes
+        });
+    if !cached {
+        let lines = build_vertex_vectors(
+            mind_model.vertex_vector_lines.take(),
+            vertex_vectors,
+            use_animation,
+            mind_model,
         );
+        vectors.update(&lines.positions, &lines.colors);
+        mind_model.vertex_vector_lines = Some(lines);
+    }
+
+    vectors.render(gl::LINES, 1.0f32, true, projection_view_matrix);
+}
+
+fn build_vertex_vectors(
+    lines: Option<VertexVectorLines>,
+    vertex_vectors: &config_json::VertexVectorsJson,
+    use_animation: bool,
+    mind_model: &MindModel,
+) -> VertexVectorLines {
+    let mut lines = lines.unwrap_or(VertexVectorLines {
+        joints_transforms: vec![],
+        settings: vertex_vectors.clone(),
+        show_meshes: vec![],
+        positions: vec![],
+        colors: vec![],
+    });
+    lines.joints_transforms.clear();
+    if use_animation {
+        lines
+            .joints_transforms
+            .extend_from_slice(&mind_model.joints_transforms);
     }
+    lines.settings.clone_from(vertex_vectors);
+    lines.show_meshes.clone_from(&mind_model.show_meshes);
+    lines.positions.clear();
+    lines.colors.clear();
+
+    let Some(skin_vectors) = &mind_model.skin_vectors else {
+        return lines;
+    };
+    let skin = &mind_model.skin;
+
+    let mut drawn = vec![false; skin.vertices.len()];
+    for (i, mesh) in skin.meshes.iter().enumerate() {
+        if !mind_model.show_meshes[i] || vertex_vectors.excluded_meshes.contains(&mesh.submesh.name)
+        {
+            continue;
+        }
+
+        let start = (mesh.submesh.indices_offset as usize).min(skin.indices.len());
+        let end = (start + mesh.submesh.indices_count as usize).min(skin.indices.len());
+        for index in skin.indices[start..end].iter() {
+            if let Some(drawn) = drawn.get_mut(*index as usize) {
+                *drawn = true;
+            }
+        }
+    }
+
+    for vertex in (0..skin.vertices.len()).filter(|vertex| drawn[*vertex]) {
+        let skin_matrix = if use_animation {
+            skin.skin_matrix(&mind_model.joints_transforms, vertex)
+        } else {
+            glam::Mat4::IDENTITY
+        };
+        let origin = skin_matrix.transform_point3(skin.vertices[vertex]);
+
+        if vertex_vectors.show_normals {
+            let normal = skin.normals[vertex];
+            let color = if normal.is_nan() || (normal.length() - 1.0f32).abs() > 0.01f32 {
+                INVALID_NORMAL_COLOR
+            } else if skin_vectors.flipped_normals[vertex] {
+                FLIPPED_NORMAL_COLOR
+            } else {
+                NORMAL_COLOR
+            };
+            let direction = glam::Mat3::from_mat4(skin_matrix)
+                .inverse()
+                .transpose()
+                .mul_vec3(normal)
+                .normalize_or_zero();
+            lines.positions.push(origin.extend(1.0f32));
+            lines
+                .positions
+                .push((origin + direction * vertex_vectors.length).extend(1.0f32));
+            lines.colors.push(color);
+            lines.colors.push(color);
+        }
+
+        let tangent = skin_vectors.tangents[vertex];
+        if vertex_vectors.show_tangents && tangent != glam::Vec3::ZERO {
+            let direction = skin_matrix.transform_vector3(tangent).normalize_or_zero();
+            lines.positions.push(origin.extend(1.0f32));
+            lines
+                .positions
+                .push((origin + direction * vertex_vectors.length).extend(1.0f32));
+            lines.colors.push(TANGENT_COLOR);
+            lines.colors.push(TANGENT_COLOR);
+        }
+    }
+
+    lines
+}
+
+pub enum Pick {
+    Joint(usize),
+    Mesh(usize),
+}
+
+fn pick_model(
+    mouse: glam::Vec2,
+    display_size: [f32; 2],
+    options: &config_json::OptionsJson,
+    skinned: bool,
+    projection_view_matrix: &glam::Mat4,
+    mind_model: &MindModel,
+) -> Option<(Pick, f32)> {
+    if options.show_skeleton_joints || options.show_skeleton_bones {
+        let nearest_joint = mind_model
+            .skeleton
+            .joints
+            .iter()
+            .enumerate()
+            .filter_map(|(i, joint)| {
+                let global_matrix = if skinned {
+                    mind_model.joints_transforms[i] * joint.global_matrix
+                } else {
+                    joint.global_matrix
+                };
+                let position = joint_position(&global_matrix);
+                picking::project_to_screen(projection_view_matrix, display_size, position)
+                    .map(|screen| (i, screen.distance(mouse), position))
+            })
+            .filter(|(_, distance, _)| *distance < 8.0f32)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+
+        if let Some((joint, _, position)) = nearest_joint {
+            let depth = (*projection_view_matrix * position.extend(1.0f32)).w;
+            return Some((Pick::Joint(joint), depth));
+        }
+    }
+
+    let (origin, direction) = picking::ray_from_screen(projection_view_matrix, display_size, mouse);
+    let positions =
+        picking::skinned_positions(&mind_model.skin, &mind_model.joints_transforms, skinned);
+    picking::pick_mesh(
+        &mind_model.skin,
+        &positions,
+        &mind_model.show_meshes,
+        origin,
+        direction,
+    )
+    .map(|(mesh, point)| {
+        let depth = (*projection_view_matrix * point.extend(1.0f32)).w;
+        (Pick::Mesh(mesh), depth)
+    })
+}
+
+fn render_mesh_outline(
+    outline: &mut Lines,
+    selected_mesh: usize,
+    skinned: bool,
+    camera_position: glam::Vec3,
+    projection_view_matrix: &glam::Mat4,
+    mind_model: &mut MindModel,
+) {
+    if !matches!(&mind_model.mesh_outline, Some(mesh_outline) if mesh_outline.mesh == selected_mesh)
+    {
+        mind_model.mesh_outline = Some(MeshOutline::new(&mind_model.skin, selected_mesh));
+    }
+    let Some(mesh_outline) = &mind_model.mesh_outline else {
+        return;
+    };
+
+    let positions =
+        picking::skinned_positions(&mind_model.skin, &mind_model.joints_transforms, skinned);
+    let lines = mesh_outline.lines(&positions, camera_position);
+    outline.update(
+        &lines,
+        &vec![glam::vec3(1.0f32, 0.6f32, 0.0f32); lines.len()],
+    );
+    outline.render(gl::LINES, 3.0f32, false, projection_view_matrix);
+}
+
+fn render_joint_highlight(
+    highlight: &mut Lines,
+    selected_joint: usize,
+    use_animation: bool,
+    projection_view_matrix: &glam::Mat4,
+    mind_model: &MindModel,
+) {
+    let joint_position = |joint_index: usize| {
+        let global_matrix = mind_model.skeleton.joints[joint_index].global_matrix;
+        if use_animation {
+            joint_position(&(mind_model.joints_transforms[joint_index] * global_matrix))
+        } else {
+            joint_position(&global_matrix)
+        }
+        .extend(1.0f32)
+    };
+
+    let joint = &mind_model.skeleton.joints[selected_joint];
+    let highlight_color = glam::vec3(1.0f32, 0.6f32, 0.0f32);
+
+    let subtree_color = glam::vec3(1.0f32, 0.85f32, 0.4f32);
+    let subtree = mind_model.skeleton.subtree(selected_joint);
+
+    let mut positions: Vec<glam::Vec4> = vec![];
+    let mut colors: Vec<glam::Vec3> = vec![];
+    if joint.parent_id != -1 {
+        positions.push(joint_position(joint.parent_id as usize));
+        positions.push(joint_position(selected_joint));
+        colors.extend([highlight_color; 2]);
+    }
+    for joint_index in subtree.iter() {
+        let color = if *joint_index == selected_joint {
+            highlight_color
+        } else {
+            subtree_color
+        };
+        for child in mind_model.skeleton.joints[*joint_index].children.iter() {
+            positions.push(joint_position(*joint_index));
+            positions.push(joint_position(*child));
+            colors.extend([color; 2]);
+        }
+    }
+    highlight.update(&positions, &colors);
+    highlight.render(gl::LINES, 4.0f32, false, projection_view_matrix);
+
+    let subtree_positions: Vec<glam::Vec4> =
+        subtree.iter().skip(1).map(|i| joint_position(*i)).collect();
+    highlight.update(
+        &subtree_positions,
+        &vec![subtree_color; subtree_positions.len()],
+    );
+    unsafe {
+        gl::PointSize(6.0f32);
+    }
+    highlight.render(gl::POINTS, 1.0f32, false, projection_view_matrix);
+
+    highlight.update(&[joint_position(selected_joint)], &[highlight_color]);
+    unsafe {
+        gl::PointSize(10.0f32);
+    }
+    highlight.render(gl::POINTS, 1.0f32, false, projection_view_matrix);
+    unsafe {
+        gl::PointSize(4.0f32);
+    }
+}
+
+fn timeline_length(config_json: &config_json::ConfigJson, mind_models: &[MindModel]) -> f32 {
+    mind_models
+        .iter()
+        .zip(config_json.options.iter())
+        .filter(|(_, options)| options.show && options.use_animation)
+        .map(|(mind_model, options)| {
+            let animation = &mind_model.animations[mind_model.animation_selected];
+            options.timeline_offset + animation.duration / options.timeline_speed
+        })
+        .fold(0.0f32, f32::max)
+}
+
+fn advance_timeline(
+    config_json: &mut config_json::ConfigJson,
+    mind_models: &[MindModel],
+    delta_time: f32,
+) {
+    if config_json.timeline_playing {
+        let timeline_length = timeline_length(config_json, mind_models);
+
+        config_json.timeline_time += delta_time * config_json.timeline_speed;
+        if config_json.timeline_time > timeline_length {
+            if config_json.timeline_loop {
+                config_json.timeline_time = 0.0f32;
+            } else {
+                config_json.timeline_time = timeline_length;
+                config_json.timeline_playing = false;
+            }
+        }
+    }
+}
+
+fn timeline_time(
+    timeline_time: f32,
+    options: &config_json::OptionsJson,
+    animation: &Animation,
+) -> f32 {
+    let local_time = (timeline_time - options.timeline_offset) * options.timeline_speed;
+    if local_time <= 0.0f32 {
+        0.0f32
+    } else if local_time <= animation.duration {
+        local_time
+    } else {
+        match options.timeline_end {
+            TimelineEnd::Hold => animation.duration,
+            TimelineEnd::Loop if animation.duration > 0.0f32 => local_time % animation.duration,
+            TimelineEnd::Loop | TimelineEnd::Stop => 0.0f32,
+        }
+    }
+}
+
+fn accumulate_root_motion(
+    options: &config_json::OptionsJson,
+    mind_model: &mut MindModel,
+    range_start: f32,
+    range_end: f32,
+) {
+    if options.accumulate_root_motion {
+        let animation = &mind_model.animations[mind_model.animation_selected];
+        match options.playback_mode {
+            PlaybackMode::Forward => mind_model.root_motion.accumulate(
+                animation,
+                &mind_model.skeleton,
+                range_start,
+                range_end,
+            ),
+            PlaybackMode::Reverse => mind_model.root_motion.accumulate(
+                animation,
+                &mind_model.skeleton,
+                range_end,
+                range_start,
+            ),
+            PlaybackMode::PingPong => {}
+        }
+    }
+}
+
+fn animation_range(options: &config_json::OptionsJson, animation: &Animation) -> (f32, f32) {
+    if options.use_animation_range {
+        let range_start = options.animation_range[0].clamp(0.0f32, animation.duration);
+        let range_end = options.animation_range[1].clamp(range_start, animation.duration);
+        (range_start, range_end)
+    } else {
+        (0.0f32, animation.duration)
+    }
+}
+
+fn evaluated_time(options: &config_json::OptionsJson, animation: &Animation) -> f32 {
+    if options.snap_to_frames {
+        animation.snap_time(options.animation_time)
+    } else {
+        options.animation_time
+    }
+}
+
+fn step_animation(options: &mut config_json::OptionsJson, mind_model: &MindModel, frames: i32) {
+    let animation = &mind_model.animations[mind_model.animation_selected];
+    let (range_start, range_end) = animation_range(options, animation);
+
+    options.play_animation = false;
+    options.animation_time = (animation.snap_time(options.animation_time)
+        + frames as f32 * animation.frame_delay)
+        .clamp(range_start, range_end);
 }
 
 fn read_to_u8(path: &Path) -> Vec<u8> {